@@ -61,6 +61,7 @@ impl App {
                 program: system_shell,
                 ..Default::default()
             },
+            ..Default::default()
         };
 
         let custom_bindings = vec![
@@ -102,9 +103,29 @@ impl App {
         let custom_bindings = generate_bindings!(
             KeyboardBinding;
             "l", Modifiers::SHIFT; BindingAction::Char('K');
+            "=", Modifiers::CTRL; BindingAction::IncreaseFontSize;
+            "-", Modifiers::CTRL; BindingAction::DecreaseFontSize;
+            "t", Modifiers::CTRL | Modifiers::SHIFT; BindingAction::Custom("rename".into());
         );
         term.handle(iced_term::Command::AddBindings(custom_bindings));
 
+        // Default bindings can be removed, Ctrl+Shift+C is not a copy anymore
+        let copy_binding = "ctrl+shift+c".parse().unwrap();
+        term.handle(iced_term::Command::RemoveBindings(vec![copy_binding]));
+
+        // Chords are pressed one key after another, like a tmux prefix
+        let chord_bindings = vec![
+            (
+                vec!["ctrl+a".parse().unwrap(), "c".parse().unwrap()],
+                BindingAction::Copy,
+            ),
+            (
+                vec!["ctrl+a".parse().unwrap(), "v".parse().unwrap()],
+                BindingAction::Paste,
+            ),
+        ];
+        term.handle(iced_term::Command::AddChordBindings(chord_bindings));
+
         (
             Self {
                 title: String::from("custom_bindings"),
@@ -124,15 +145,18 @@ impl App {
 
     fn update(&mut self, event: Event) -> Task<Event> {
         match event {
-            Event::Terminal(iced_term::Event::BackendCall(_, cmd)) => {
-                match self.term.handle(iced_term::Command::ProxyToBackend(cmd))
-                {
+            Event::Terminal(event) => {
+                match self.term.handle(event.into()) {
                     iced_term::actions::Action::Shutdown => {
                         return window::latest().and_then(window::close)
                     },
                     iced_term::actions::Action::ChangeTitle(title) => {
                         self.title = title;
                     },
+                    // Actions of BindingAction::Custom bindings come back here
+                    iced_term::actions::Action::Custom(name) => {
+                        self.title = format!("custom_bindings: {}", name);
+                    },
                     _ => {},
                 }
             },
@@ -55,6 +55,7 @@ impl App {
                 program: system_shell,
                 ..Default::default()
             },
+            ..Default::default()
         };
 
         (
@@ -77,17 +78,14 @@ impl App {
 
     fn update(&mut self, event: Event) -> Task<Event> {
         match event {
-            Event::Terminal(iced_term::Event::BackendCall(_, cmd)) => {
-                match self.term.handle(iced_term::Command::ProxyToBackend(cmd))
-                {
-                    iced_term::actions::Action::Shutdown => {
-                        return window::latest().and_then(window::close)
-                    },
-                    iced_term::actions::Action::ChangeTitle(title) => {
-                        self.title = title;
-                    },
-                    _ => {},
-                }
+            Event::Terminal(event) => match self.term.handle(event.into()) {
+                iced_term::actions::Action::Shutdown => {
+                    return window::latest().and_then(window::close)
+                },
+                iced_term::actions::Action::ChangeTitle(title) => {
+                    self.title = title;
+                },
+                _ => {},
             },
         }
 
@@ -67,6 +67,7 @@ impl App {
                 program: system_shell,
                 ..Default::default()
             },
+            ..Default::default()
         };
 
         let tab = iced_term::Terminal::new(
@@ -148,9 +149,9 @@ impl App {
                     return window::latest().and_then(window::close);
                 }
             },
-            Event::Terminal(iced_term::Event::BackendCall(id, cmd)) => {
-                if let Some(tab) = self.tabs.get_mut(&id) {
-                    if tab.handle(iced_term::Command::ProxyToBackend(cmd))
+            Event::Terminal(event) => {
+                if let Some(tab) = self.tabs.get_mut(&event.terminal_id()) {
+                    if tab.handle(event.into())
                         == iced_term::actions::Action::Shutdown
                     {
                         if let Some(current_pane) = self.focus {
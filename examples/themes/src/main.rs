@@ -4,6 +4,8 @@ use iced::widget::{button, column, container, row};
 use iced::{window, Font, Length, Size, Subscription, Task, Theme};
 use iced_term::TerminalView;
 
+const DRACULA_SCHEME: &str = include_str!("../assets/schemes/dracula.toml");
+
 const TERM_FONT_JET_BRAINS_BYTES: &[u8] = include_bytes!(
     "../assets/fonts/JetBrains/JetBrainsMonoNerdFontMono-Bold.ttf"
 );
@@ -57,6 +59,7 @@ impl App {
                 program: system_shell,
                 ..Default::default()
             },
+            ..Default::default()
         };
 
         (
@@ -82,17 +85,14 @@ impl App {
             Event::ThemeChanged(palette) => {
                 self.term.handle(iced_term::Command::ChangeTheme(palette));
             },
-            Event::Terminal(iced_term::Event::BackendCall(_, cmd)) => {
-                match self.term.handle(iced_term::Command::ProxyToBackend(cmd))
-                {
-                    iced_term::actions::Action::Shutdown => {
-                        return window::latest().and_then(window::close)
-                    },
-                    iced_term::actions::Action::ChangeTitle(title) => {
-                        self.title = title;
-                    },
-                    _ => {},
-                }
+            Event::Terminal(event) => match self.term.handle(event.into()) {
+                iced_term::actions::Action::Shutdown => {
+                    return window::latest().and_then(window::close)
+                },
+                iced_term::actions::Action::ChangeTitle(title) => {
+                    self.title = title;
+                },
+                _ => {},
             },
         }
 
@@ -152,6 +152,16 @@ impl App {
                         ..Default::default()
                     }))
                 ),
+                // Schemes can also be imported from the Alacritty, base16,
+                // iTerm2 or Windows Terminal formats
+                button("dracula").width(Length::Fill).padding(8).on_press(
+                    Event::ThemeChanged(Box::new(
+                        iced_term::ColorPalette::from_alacritty_toml(
+                            DRACULA_SCHEME
+                        )
+                        .expect("invalid dracula color scheme")
+                    ))
+                ),
             ],
             row![TerminalView::show(&self.term).map(Event::Terminal)]
         ];
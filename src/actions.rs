@@ -1,7 +1,11 @@
+use crate::backend::BackendError;
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum Action {
     Shutdown,
     ChangeTitle(String),
+    Custom(String),
+    BackendFailed(BackendError),
     #[default]
     Ignore,
 }
@@ -1,4 +1,8 @@
 use crate::actions::Action;
+#[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+use crate::graphics::{
+    image_placements, Fence, GraphicsPty, GraphicsStore, ImagePlacement,
+};
 use crate::settings::BackendSettings;
 use alacritty_terminal::event::{
     Event, EventListener, Notify, OnResize, WindowSize,
@@ -10,24 +14,44 @@ use alacritty_terminal::selection::{Selection, SelectionRange, SelectionType};
 use alacritty_terminal::sync::FairMutex;
 use alacritty_terminal::term::search::{Match, RegexIter, RegexSearch};
 use alacritty_terminal::term::{
-    self, cell::Cell, test::TermSize, viewport_to_point, Term, TermMode,
+    self, cell::Cell, test::TermSize, viewport_to_point, Term, TermDamage,
+    TermMode,
 };
+use alacritty_terminal::vte::ansi::{ClearMode, CursorStyle, Handler};
 use alacritty_terminal::{tty, Grid};
 use iced::keyboard::Modifiers;
 use iced_core::Size;
 use std::borrow::Cow;
 use std::cmp::min;
+use std::collections::BTreeSet;
+use std::fmt;
 use std::io::Result;
 use std::ops::{Index, RangeInclusive};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+#[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+use std::sync::Mutex;
 use tokio::sync::mpsc;
 
+/// The primary device attributes reported by the terminal
+#[cfg(feature = "sixel")]
+const DEVICE_ATTRIBUTES: &str = "\x1b[?6c";
+#[cfg(feature = "sixel")]
+const SIXEL_DEVICE_ATTRIBUTES: &str = "\x1b[?62;4c";
+
 const URL_REGEX: &str = r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#;
 
 #[derive(Debug, Clone)]
 pub enum Command {
     Write(Vec<u8>),
     Scroll(i32),
+    ScrollPageUp,
+    ScrollPageDown,
+    ScrollToTop,
+    ScrollToBottom,
+    ClearHistory,
+    SelectAll,
+    Reset,
     Resize(Option<Size<f32>>, Option<Size<f32>>),
     SelectStart(SelectionType, (f32, f32)),
     SelectUpdate((f32, f32)),
@@ -75,7 +99,7 @@ pub enum LinkAction {
     Open,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct TerminalSize {
     pub cell_width: u16,
     pub cell_height: u16,
@@ -133,17 +157,22 @@ impl From<TerminalSize> for WindowSize {
 
 pub struct Backend {
     term: Arc<FairMutex<Term<EventProxy>>>,
+    is_shutdown: Arc<AtomicBool>,
+    #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+    pub(crate) graphics: Arc<Mutex<GraphicsStore>>,
     size: TerminalSize,
     notifier: Notifier,
     last_content: RenderableContent,
+    damage: Damage,
     pub(crate) url_regex: RegexSearch,
 }
 
 impl Backend {
     pub fn new(
         id: u64,
-        pty_event_proxy_sender: mpsc::Sender<Event>,
+        event_proxy: EventProxy,
         settings: BackendSettings,
+        cursor_style: CursorStyle,
     ) -> Result<Self> {
         let pty_config = tty::Options {
             shell: Some(tty::Shell::new(settings.program, settings.args)),
@@ -152,39 +181,73 @@ impl Backend {
             ..tty::Options::default()
         };
 
-        let config = term::Config::default();
+        let config = term::Config {
+            default_cursor_style: cursor_style,
+            ..term::Config::default()
+        };
         let terminal_size = TerminalSize::default();
         let pty = tty::new(&pty_config, terminal_size.into(), id)?;
-
-        let event_proxy = EventProxy(pty_event_proxy_sender);
-
         let mut term = Term::new(config, &terminal_size, event_proxy.clone());
 
         let cursor = term.grid_mut().cursor_cell().clone();
+        let mut grid = GridSnapshot::default();
+        grid.update(term.grid(), &Damage::Full);
 
         let initial_content = RenderableContent {
-            grid: term.grid().clone(),
+            grid,
             selectable_range: None,
             terminal_mode: *term.mode(),
             terminal_size,
             cursor: cursor.clone(),
+            cursor_style: term.cursor_style(),
             hovered_hyperlink: None,
+            #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+            images: Vec::new(),
         };
 
         let term = Arc::new(FairMutex::new(term));
+        #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+        let graphics = Arc::new(Mutex::new(GraphicsStore::default()));
+        #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+        let pty = GraphicsPty::new(
+            pty,
+            graphics.clone(),
+            term.clone(),
+            event_proxy.clone(),
+        );
 
-        let pty_event_loop =
-            EventLoop::new(term.clone(), event_proxy, pty, false, false)?;
+        let pty_event_loop = EventLoop::new(
+            term.clone(),
+            event_proxy.clone(),
+            pty,
+            false,
+            false,
+        )?;
 
         let notifier = Notifier(pty_event_loop.channel());
 
-        let _ = pty_event_loop.spawn();
+        let pty_event_loop = pty_event_loop.spawn();
+
+        // The event loop stops on the child exit or on a PTY error,
+        // the stop is reported unless the backend is dropped
+        let is_shutdown = Arc::new(AtomicBool::new(false));
+        let is_event_loop_shutdown = is_shutdown.clone();
+        std::thread::spawn(move || {
+            let _ = pty_event_loop.join();
+            if !is_event_loop_shutdown.load(Ordering::Acquire) {
+                event_proxy.send_stopped();
+            }
+        });
 
         Ok(Self {
             term: term.clone(),
+            is_shutdown,
+            #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+            graphics,
             size: terminal_size,
             notifier,
             last_content: initial_content,
+            damage: Damage::Full,
             url_regex: RegexSearch::new(URL_REGEX).expect("invalid url regexp"),
         })
     }
@@ -215,6 +278,27 @@ impl Backend {
             Command::Scroll(delta) => {
                 self.scroll(&mut term, delta);
             },
+            Command::ScrollPageUp => {
+                term.grid_mut().scroll_display(Scroll::PageUp);
+            },
+            Command::ScrollPageDown => {
+                term.grid_mut().scroll_display(Scroll::PageDown);
+            },
+            Command::ScrollToTop => {
+                term.grid_mut().scroll_display(Scroll::Top);
+            },
+            Command::ScrollToBottom => {
+                term.grid_mut().scroll_display(Scroll::Bottom);
+            },
+            Command::ClearHistory => {
+                term.clear_screen(ClearMode::Saved);
+            },
+            Command::SelectAll => {
+                self.select_all(&mut term);
+            },
+            Command::Reset => {
+                term.reset_state();
+            },
             Command::Resize(layout_size, font_measure) => {
                 self.resize(&mut term, layout_size, font_measure);
             },
@@ -243,28 +327,40 @@ impl Backend {
     ) {
         match link_action {
             LinkAction::Hover => {
-                self.last_content.hovered_hyperlink = self.regex_match_at(
+                let hovered_hyperlink = self.regex_match_at(
                     terminal,
                     point,
                     &mut self.url_regex.clone(),
                 );
+                self.set_hovered_hyperlink(hovered_hyperlink);
             },
             LinkAction::Clear => {
-                self.last_content.hovered_hyperlink = None;
+                self.set_hovered_hyperlink(None);
             },
             LinkAction::Open => {
-                self.open_link();
+                self.open_link(terminal);
             },
         };
     }
 
-    fn open_link(&self) {
+    fn set_hovered_hyperlink(
+        &mut self,
+        hovered_hyperlink: Option<RangeInclusive<Point>>,
+    ) {
+        // The underline of the hyperlink is drawn by the view
+        if self.last_content.hovered_hyperlink != hovered_hyperlink {
+            self.last_content.hovered_hyperlink = hovered_hyperlink;
+            self.damage = Damage::Full;
+        }
+    }
+
+    fn open_link(&self, terminal: &Term<EventProxy>) {
         if let Some(range) = &self.last_content.hovered_hyperlink {
             let start = range.start();
             let end = range.end();
 
-            let mut url = String::from(self.last_content.grid.index(*start).c);
-            for indexed in self.last_content.grid.iter_from(*start) {
+            let mut url = String::from(terminal.grid().index(*start).c);
+            for indexed in terminal.grid().iter_from(*start) {
                 url.push(indexed.c);
                 if indexed.point == *end {
                     break;
@@ -379,6 +475,19 @@ impl Backend {
         ));
     }
 
+    fn select_all(&self, terminal: &mut Term<EventProxy>) {
+        let mut selection = Selection::new(
+            SelectionType::Simple,
+            Point::new(terminal.topmost_line(), Column(0)),
+            Side::Left,
+        );
+        selection.update(
+            Point::new(terminal.bottommost_line(), terminal.last_column()),
+            Side::Right,
+        );
+        terminal.selection = Some(selection);
+    }
+
     fn update_selection(
         &mut self,
         terminal: &mut Term<EventProxy>,
@@ -477,17 +586,9 @@ impl Backend {
         }
     }
 
+    /// The selected text, including the lines scrolled out of the viewport
     pub fn selectable_content(&self) -> String {
-        let content = self.renderable_content();
-        let mut result = String::new();
-        if let Some(range) = content.selectable_range {
-            for indexed in content.grid.display_iter() {
-                if range.contains(indexed.point) {
-                    result.push(indexed.c);
-                }
-            }
-        }
-        result
+        self.term.lock().selection_to_string().unwrap_or_default()
     }
 
     pub fn sync(&mut self) {
@@ -502,12 +603,60 @@ impl Backend {
             None => None,
         };
 
+        let damage = match terminal.damage() {
+            TermDamage::Full => Damage::Full,
+            TermDamage::Partial(lines) => {
+                Damage::Lines(lines.map(|bounds| bounds.line).collect())
+            },
+        };
+        terminal.reset_damage();
+        self.damage.merge(damage);
+
+        // The whole viewport is changed on scroll, resize, selection or mode
+        // change, and some of them are not reported by the terminal damage
+        let content = &self.last_content;
+        if content.grid.display_offset() != terminal.grid().display_offset()
+            || content.selectable_range != selectable_range
+            || content.terminal_mode != *terminal.mode()
+            || content.terminal_size != self.size
+        {
+            self.damage = Damage::Full;
+        }
+        // The attached image markers are not reported by the terminal damage
+        #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+        if self
+            .graphics
+            .lock()
+            .expect("graphics store is poisoned")
+            .take_marked()
+        {
+            self.damage = Damage::Full;
+        }
+
         let cursor = terminal.grid_mut().cursor_cell().clone();
-        self.last_content.grid = terminal.grid().clone();
+        self.last_content.grid.update(terminal.grid(), &self.damage);
         self.last_content.selectable_range = selectable_range;
         self.last_content.cursor = cursor.clone();
+        self.last_content.cursor_style = terminal.cursor_style();
         self.last_content.terminal_mode = *terminal.mode();
         self.last_content.terminal_size = self.size;
+        #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+        image_placements(
+            &self.last_content.grid,
+            &self.graphics.lock().expect("graphics store is poisoned"),
+            &mut self.last_content.images,
+        );
+    }
+
+    /// Returns the damage collected since the last call
+    pub(crate) fn take_damage(&mut self) -> Damage {
+        std::mem::take(&mut self.damage)
+    }
+
+    /// Marks the whole viewport as changed,
+    /// e.g. when the theme or the font is changed
+    pub(crate) fn damage_all(&mut self) {
+        self.damage = Damage::Full;
     }
 
     pub fn renderable_content(&self) -> &RenderableContent {
@@ -547,11 +696,121 @@ fn visible_regex_match_iter<'a>(
         .take_while(move |rm| rm.start().line <= viewport_end)
 }
 
+/// The viewport lines that need to be drawn again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Damage {
+    Full,
+    Lines(BTreeSet<usize>),
+}
+
+impl Default for Damage {
+    fn default() -> Self {
+        Self::Lines(BTreeSet::new())
+    }
+}
+
+impl Damage {
+    fn contains(&self, line: usize) -> bool {
+        match self {
+            Damage::Full => true,
+            Damage::Lines(lines) => lines.contains(&line),
+        }
+    }
+
+    fn merge(&mut self, other: Damage) {
+        match (self, other) {
+            (Damage::Full, _) => {},
+            (this, Damage::Full) => *this = Damage::Full,
+            (Damage::Lines(lines), Damage::Lines(other)) => lines.extend(other),
+        }
+    }
+}
+
+/// The copy of the viewport lines of the terminal grid,
+/// the history is read from the locked terminal instead
+#[derive(Debug, Default)]
+pub struct GridSnapshot {
+    cells: Vec<Cell>,
+    columns: usize,
+    screen_lines: usize,
+    display_offset: usize,
+    cursor_point: Point,
+}
+
+impl GridSnapshot {
+    /// Copies the damaged lines, the cells allocation is reused
+    pub(crate) fn update(&mut self, grid: &Grid<Cell>, damage: &Damage) {
+        let is_full = self.columns != grid.columns()
+            || self.screen_lines != grid.screen_lines()
+            || self.display_offset != grid.display_offset();
+
+        self.columns = grid.columns();
+        self.screen_lines = grid.screen_lines();
+        self.display_offset = grid.display_offset();
+        self.cursor_point = grid.cursor.point;
+        self.cells
+            .resize(self.columns * self.screen_lines, Cell::default());
+
+        for line in 0..self.screen_lines {
+            if !is_full && !damage.contains(line) {
+                continue;
+            }
+
+            let row = &grid[Line(line as i32 - self.display_offset as i32)];
+            let start = line * self.columns;
+            self.cells[start..start + self.columns].clone_from_slice(&row[..]);
+        }
+    }
+
+    pub fn display_offset(&self) -> usize {
+        self.display_offset
+    }
+
+    pub fn cursor_point(&self) -> Point {
+        self.cursor_point
+    }
+}
+
+impl Dimensions for GridSnapshot {
+    fn total_lines(&self) -> usize {
+        self.screen_lines
+    }
+
+    fn screen_lines(&self) -> usize {
+        self.screen_lines
+    }
+
+    fn columns(&self) -> usize {
+        self.columns
+    }
+}
+
+impl Index<Line> for GridSnapshot {
+    type Output = [Cell];
+
+    fn index(&self, line: Line) -> &[Cell] {
+        let line = (line.0 + self.display_offset as i32) as usize;
+        let start = line * self.columns;
+        &self.cells[start..start + self.columns]
+    }
+}
+
+impl Index<Point> for GridSnapshot {
+    type Output = Cell;
+
+    fn index(&self, point: Point) -> &Cell {
+        &self[point.line][point.column.0]
+    }
+}
+
 pub struct RenderableContent {
-    pub grid: Grid<Cell>,
+    pub grid: GridSnapshot,
     pub hovered_hyperlink: Option<RangeInclusive<Point>>,
+    #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+    pub(crate) images: Vec<ImagePlacement>,
     pub selectable_range: Option<SelectionRange>,
     pub cursor: Cell,
+    pub cursor_style: CursorStyle,
     pub terminal_mode: TermMode,
     pub terminal_size: TerminalSize,
 }
@@ -559,10 +818,13 @@ pub struct RenderableContent {
 impl Default for RenderableContent {
     fn default() -> Self {
         Self {
-            grid: Grid::new(0, 0, 0),
+            grid: GridSnapshot::default(),
             hovered_hyperlink: None,
+            #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+            images: Vec::new(),
             selectable_range: None,
             cursor: Cell::default(),
+            cursor_style: CursorStyle::default(),
             terminal_mode: TermMode::empty(),
             terminal_size: TerminalSize::default(),
         }
@@ -571,15 +833,230 @@ impl Default for RenderableContent {
 
 impl Drop for Backend {
     fn drop(&mut self) {
+        self.is_shutdown.store(true, Ordering::Release);
         let _ = self.notifier.0.send(Msg::Shutdown);
     }
 }
 
+/// The failure of the terminal backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackendError {
+    /// The shell has exited without an exit code,
+    /// e.g. it was killed by a signal
+    ChildKilled,
+    /// The PTY event loop has stopped on a read or write error
+    PtyFailed,
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackendError::ChildKilled => write!(f, "the shell was killed"),
+            BackendError::PtyFailed => write!(f, "the PTY has failed"),
+        }
+    }
+}
+
+impl std::error::Error for BackendError {}
+
+pub(crate) enum BackendEvent {
+    Terminal(Event),
+    /// The PTY event loop has stopped, it's a failure
+    /// if the terminal hasn't exited before
+    Stopped,
+}
+
+/// Creates the channel of the terminal events. The events are never
+/// dropped, except the wakeups that are merged until one is received
+pub(crate) fn event_channel() -> (EventProxy, EventReceiver) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let has_pending_wakeup = Arc::new(AtomicBool::new(false));
+    let proxy = EventProxy {
+        sender,
+        has_pending_wakeup: has_pending_wakeup.clone(),
+        #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+        fence: Arc::default(),
+    };
+    let receiver = EventReceiver {
+        receiver,
+        has_pending_wakeup,
+    };
+
+    (proxy, receiver)
+}
+
 #[derive(Clone)]
-pub struct EventProxy(mpsc::Sender<Event>);
+pub struct EventProxy {
+    sender: mpsc::UnboundedSender<BackendEvent>,
+    has_pending_wakeup: Arc<AtomicBool>,
+    #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+    fence: Arc<Fence>,
+}
+
+impl EventProxy {
+    pub(crate) fn send_stopped(&self) {
+        let _ = self.sender.send(BackendEvent::Stopped);
+    }
+
+    #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+    pub(crate) fn fence(&self) -> Arc<Fence> {
+        self.fence.clone()
+    }
+}
 
 impl EventListener for EventProxy {
     fn send_event(&self, event: Event) {
-        let _ = self.0.try_send(event);
+        // The primary device attributes advertise the sixel graphics,
+        // the reply is sent in order with the other ones
+        #[cfg(feature = "sixel")]
+        let event = match event {
+            Event::PtyWrite(text) if text == DEVICE_ATTRIBUTES => {
+                Event::PtyWrite(SIXEL_DEVICE_ATTRIBUTES.to_string())
+            },
+            event => event,
+        };
+
+        // The fences of the graphics PTY are not copied
+        #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+        if let Event::ClipboardStore(_, text) = &event {
+            if self.fence.receive(text) {
+                return;
+            }
+        }
+
+        if let Event::Wakeup = event {
+            if self.has_pending_wakeup.swap(true, Ordering::AcqRel) {
+                return;
+            }
+        }
+
+        // The receiver is closed only when the terminal is dropped
+        let _ = self.sender.send(BackendEvent::Terminal(event));
+    }
+}
+
+pub(crate) struct EventReceiver {
+    receiver: mpsc::UnboundedReceiver<BackendEvent>,
+    has_pending_wakeup: Arc<AtomicBool>,
+}
+
+impl EventReceiver {
+    pub(crate) async fn recv(&mut self) -> Option<BackendEvent> {
+        let event = self.receiver.recv().await;
+        if let Some(BackendEvent::Terminal(Event::Wakeup)) = event {
+            // The next wakeup is sent, the changes made after this one
+            // are not synced yet
+            self.has_pending_wakeup.store(false, Ordering::Release);
+        }
+
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::vte::ansi::Processor;
+
+    #[test]
+    fn merge_damage() {
+        let mut damage = Damage::default();
+        damage.merge(Damage::Lines(BTreeSet::from([1, 3])));
+        damage.merge(Damage::Lines(BTreeSet::from([3, 5])));
+        assert_eq!(damage, Damage::Lines(BTreeSet::from([1, 3, 5])));
+
+        damage.merge(Damage::Full);
+        assert_eq!(damage, Damage::Full);
+
+        damage.merge(Damage::Lines(BTreeSet::from([2])));
+        assert_eq!(damage, Damage::Full);
+    }
+
+    #[test]
+    fn update_damaged_lines_of_grid_snapshot() {
+        let mut grid = Grid::<Cell>::new(3, 2, 10);
+        grid[Line(0)][Column(0)].c = 'a';
+        grid[Line(2)][Column(1)].c = 'b';
+
+        let mut snapshot = GridSnapshot::default();
+        snapshot.update(&grid, &Damage::Lines(BTreeSet::new()));
+        assert_eq!(snapshot.screen_lines(), 3);
+        assert_eq!(snapshot.columns(), 2);
+        assert_eq!(snapshot[Point::new(Line(0), Column(0))].c, 'a');
+        assert_eq!(snapshot[Point::new(Line(2), Column(1))].c, 'b');
+
+        // Only the damaged lines are copied again
+        grid[Line(0)][Column(0)].c = 'c';
+        grid[Line(1)][Column(0)].c = 'd';
+        snapshot.update(&grid, &Damage::Lines(BTreeSet::from([1])));
+        assert_eq!(snapshot[Point::new(Line(0), Column(0))].c, 'a');
+        assert_eq!(snapshot[Point::new(Line(1), Column(0))].c, 'd');
+
+        snapshot.update(&grid, &Damage::Full);
+        assert_eq!(snapshot[Point::new(Line(0), Column(0))].c, 'c');
+    }
+
+    #[tokio::test]
+    async fn deliver_control_events_under_load() {
+        const REPLIES: usize = 1000;
+
+        let (proxy, mut receiver) = event_channel();
+        let output = std::thread::spawn(move || {
+            let size = TermSize::new(80, 24);
+            let mut term =
+                Term::new(term::Config::default(), &size, proxy.clone());
+            let mut parser: Processor = Processor::new();
+            for i in 0..REPLIES {
+                // Device attributes request and title change among the text
+                let chunk = format!(
+                    "{}\x1b[c\x1b]2;title {}\x07\r\n",
+                    "x".repeat(500),
+                    i
+                );
+                parser.advance(&mut term, chunk.as_bytes());
+                proxy.send_event(Event::Wakeup);
+            }
+            proxy.send_event(Event::Exit);
+        });
+
+        let mut replies = 0;
+        let mut titles = Vec::new();
+        let mut wakeups = 0;
+        while let Some(BackendEvent::Terminal(event)) = receiver.recv().await {
+            match event {
+                Event::PtyWrite(_) => replies += 1,
+                Event::Title(title) => titles.push(title),
+                Event::Wakeup => wakeups += 1,
+                Event::Exit => break,
+                _ => {},
+            }
+        }
+        output.join().unwrap();
+
+        assert_eq!(replies, REPLIES);
+        assert_eq!(titles.len(), REPLIES);
+        assert_eq!(titles.last().unwrap(), &format!("title {}", REPLIES - 1));
+        assert!((1..=REPLIES).contains(&wakeups));
+    }
+
+    #[cfg(feature = "sixel")]
+    #[tokio::test]
+    async fn report_sixel_device_attributes_in_order() {
+        let (proxy, mut receiver) = event_channel();
+        let size = TermSize::new(80, 24);
+        let mut term = Term::new(term::Config::default(), &size, proxy);
+        let mut parser: Processor = Processor::new();
+        // The device attributes are requested after the cursor position
+        parser.advance(&mut term, b"\x1b[6n\x1b[c");
+
+        let mut replies = Vec::new();
+        while replies.len() < 2 {
+            if let Some(BackendEvent::Terminal(Event::PtyWrite(reply))) =
+                receiver.recv().await
+            {
+                replies.push(reply);
+            }
+        }
+        assert_eq!(replies, ["\x1b[1;1R", SIXEL_DEVICE_ATTRIBUTES]);
     }
 }
@@ -1,16 +1,54 @@
 use alacritty_terminal::term::TermMode;
 use iced_core::{
-    keyboard::{key::Named, Modifiers},
+    keyboard::{
+        key::{Code, Named},
+        Modifiers,
+    },
     mouse::Button,
 };
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Clone, Hash, Debug, PartialEq, Eq)]
+#[derive(Clone, Hash, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BindingAction {
+    #[serde(alias = "copy")]
     Copy,
+    #[serde(alias = "paste")]
     Paste,
+    #[serde(rename = "char", alias = "Char")]
     Char(char),
+    #[serde(rename = "esc", alias = "Esc")]
     Esc(String),
+    #[serde(alias = "link_open")]
     LinkOpen,
+    #[serde(alias = "scroll_page_up")]
+    ScrollPageUp,
+    #[serde(alias = "scroll_page_down")]
+    ScrollPageDown,
+    #[serde(alias = "scroll_to_top")]
+    ScrollToTop,
+    #[serde(alias = "scroll_to_bottom")]
+    ScrollToBottom,
+    #[serde(alias = "increase_font_size")]
+    IncreaseFontSize,
+    #[serde(alias = "decrease_font_size")]
+    DecreaseFontSize,
+    #[serde(alias = "reset_font_size")]
+    ResetFontSize,
+    #[serde(alias = "clear_history")]
+    ClearHistory,
+    #[serde(alias = "select_all")]
+    SelectAll,
+    #[serde(alias = "reset_terminal")]
+    ResetTerminal,
+    /// The action is returned to the host as [`crate::actions::Action::Custom`]
+    #[serde(rename = "custom", alias = "Custom")]
+    Custom(String),
+    #[serde(alias = "ignore")]
     Ignore,
 }
 
@@ -18,6 +56,8 @@ pub enum BindingAction {
 pub enum InputKind {
     Char(String),
     KeyCode(Named),
+    /// The physical key, it triggers regardless of the active keyboard layout
+    Physical(Code),
     Mouse(Button),
 }
 
@@ -31,6 +71,8 @@ pub struct Binding<T> {
 
 pub type KeyboardBinding = Binding<InputKind>;
 pub type MouseBinding = Binding<InputKind>;
+/// Bindings that have to be pressed one after another, like `Ctrl+A c`
+pub type KeyboardChord = Vec<KeyboardBinding>;
 
 #[macro_export]
 macro_rules! generate_bindings {
@@ -49,6 +91,9 @@ macro_rules! generate_bindings {
             (KeyboardBinding, $key:ident) => {{
                 InputKind::KeyCode(Named::$key)
             }};
+            (KeyboardBinding, Code::$key:ident) => {{
+                InputKind::Physical(Code::$key)
+            }};
             (MouseBinding, $key:ident) => {{
                 InputKind::Mouse(Button::$key)
             }};
@@ -68,7 +113,7 @@ macro_rules! generate_bindings {
             $(_terminal_mode_exclude.insert($terminal_mode_exclude);)*
 
             let binding = $binding_type {
-                target: input_kind_match!($binding_type, $input_kind),
+                target: input_kind_match!($binding_type, $input_kind$(::$button)?),
                 modifiers: _input_modifiers,
                 terminal_mode_include: _terminal_mode_include,
                 terminal_mode_exclude: _terminal_mode_exclude,
@@ -84,6 +129,26 @@ macro_rules! generate_bindings {
 #[derive(Clone, Debug)]
 pub(crate) struct BindingsLayout {
     layout: Vec<(Binding<InputKind>, BindingAction)>,
+    /// Positions in `layout` by the input and the modifiers,
+    /// every bucket is sorted so the first match wins as in the layout
+    index: HashMap<InputKind, HashMap<Modifiers, Vec<usize>>>,
+    chords: Vec<(KeyboardChord, BindingAction)>,
+    pub(crate) modify_other_keys: bool,
+}
+
+/// The key pressed while looking for a chord, `inputs` are
+/// the same fallbacks that are used for the regular bindings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ChordKey {
+    pub(crate) inputs: Vec<InputKind>,
+    pub(crate) modifiers: Modifiers,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ChordMatch {
+    Complete(BindingAction),
+    Prefix,
+    Mismatch,
 }
 
 impl Default for BindingsLayout {
@@ -95,8 +160,12 @@ impl Default for BindingsLayout {
 impl BindingsLayout {
     pub(crate) fn new() -> Self {
         let mut layout = Self {
-            layout: default_keyboard_bindings(),
+            layout: Vec::new(),
+            index: HashMap::new(),
+            chords: Vec::new(),
+            modify_other_keys: false,
         };
+        layout.add_bindings(default_keyboard_bindings());
         layout.add_bindings(platform_keyboard_bindings());
         layout.add_bindings(mouse_default_bindings());
         layout
@@ -107,15 +176,146 @@ impl BindingsLayout {
         bindings: Vec<(Binding<InputKind>, BindingAction)>,
     ) {
         for (binding, action) in bindings {
+            let bucket = self
+                .index
+                .entry(binding.target.clone())
+                .or_default()
+                .entry(binding.modifiers)
+                .or_default();
+            match bucket
+                .iter()
+                .find(|position| self.layout[**position].0 == binding)
+            {
+                Some(position) => self.layout[*position] = (binding, action),
+                None => {
+                    bucket.push(self.layout.len());
+                    self.layout.push((binding, action));
+                },
+            }
+        }
+    }
+
+    pub(crate) fn remove_bindings(
+        &mut self,
+        bindings: Vec<Binding<InputKind>>,
+    ) {
+        self.layout
+            .retain(|(binding, _)| !bindings.contains(binding));
+        self.reindex();
+    }
+
+    fn reindex(&mut self) {
+        self.index.clear();
+        for (position, (binding, _)) in self.layout.iter().enumerate() {
+            self.index
+                .entry(binding.target.clone())
+                .or_default()
+                .entry(binding.modifiers)
+                .or_default()
+                .push(position);
+        }
+    }
+
+    /// The bindings that are triggered in the terminal mode, one per key
+    /// and in the order they were added. It is useful for a help screen.
+    pub(crate) fn active_bindings(
+        &self,
+        terminal_mode: TermMode,
+    ) -> Vec<&(Binding<InputKind>, BindingAction)> {
+        let mut positions: Vec<usize> = self
+            .index
+            .values()
+            .flat_map(HashMap::values)
+            .filter_map(|bucket| {
+                self.first_active(bucket, terminal_mode).filter(|position| {
+                    self.layout[*position].1 != BindingAction::Ignore
+                })
+            })
+            .collect();
+        positions.sort_unstable();
+        positions
+            .into_iter()
+            .map(|position| &self.layout[position])
+            .collect()
+    }
+
+    fn first_active(
+        &self,
+        bucket: &[usize],
+        terminal_mode: TermMode,
+    ) -> Option<usize> {
+        bucket.iter().copied().find(|position| {
+            let binding = &self.layout[*position].0;
+            terminal_mode.contains(binding.terminal_mode_include)
+                && !terminal_mode.intersects(binding.terminal_mode_exclude)
+        })
+    }
+
+    pub(crate) fn add_chord_bindings(
+        &mut self,
+        chords: Vec<(KeyboardChord, BindingAction)>,
+    ) {
+        for (chord, action) in chords {
+            if chord.is_empty() {
+                continue;
+            }
+
             match self
-                .layout
+                .chords
                 .iter()
-                .position(|(layout_binding, _)| layout_binding == &binding)
+                .position(|(layout_chord, _)| layout_chord == &chord)
             {
-                Some(position) => self.layout[position] = (binding, action),
-                None => self.layout.push((binding, action)),
+                Some(position) => self.chords[position] = (chord, action),
+                None => self.chords.push((chord, action)),
+            }
+        }
+    }
+
+    pub(crate) fn remove_chord_bindings(&mut self, chords: Vec<KeyboardChord>) {
+        self.chords.retain(|(chord, _)| !chords.contains(chord));
+    }
+
+    /// Matches the pending keys followed by the pressed one against
+    /// the chords. A complete chord wins over the longer ones
+    /// that start with the same keys.
+    pub(crate) fn match_chord(
+        &self,
+        pending: &[ChordKey],
+        key: &ChordKey,
+        terminal_mode: TermMode,
+    ) -> ChordMatch {
+        let keys_count = pending.len() + 1;
+        let mut is_prefix = false;
+        for (chord, action) in &self.chords {
+            if chord.len() < keys_count {
+                continue;
+            }
+
+            let is_matched = pending
+                .iter()
+                .chain(std::iter::once(key))
+                .zip(chord)
+                .all(|(key, binding)| {
+                    key.inputs.contains(&binding.target)
+                        && binding.modifiers == key.modifiers
+                        && terminal_mode.contains(binding.terminal_mode_include)
+                        && !terminal_mode
+                            .intersects(binding.terminal_mode_exclude)
+                });
+
+            if is_matched {
+                if chord.len() == keys_count {
+                    return ChordMatch::Complete(action.clone());
+                }
+                is_prefix = true;
             }
         }
+
+        if is_prefix {
+            ChordMatch::Prefix
+        } else {
+            ChordMatch::Mismatch
+        }
     }
 
     pub(crate) fn get_action(
@@ -124,18 +324,598 @@ impl BindingsLayout {
         modifiers: Modifiers,
         terminal_mode: TermMode,
     ) -> BindingAction {
-        for (binding, action) in &self.layout {
-            let is_trigered = binding.target == input
-                && binding.modifiers == modifiers
-                && terminal_mode.contains(binding.terminal_mode_include)
-                && !terminal_mode.intersects(binding.terminal_mode_exclude);
+        self.get_action_with_fallbacks(&[input], modifiers, terminal_mode)
+    }
 
-            if is_trigered {
+    /// Returns the action of the first input that has an explicit binding.
+    /// If none of them has, the xterm sequence of the first input is used.
+    pub(crate) fn get_action_with_fallbacks(
+        &self,
+        inputs: &[InputKind],
+        modifiers: Modifiers,
+        terminal_mode: TermMode,
+    ) -> BindingAction {
+        for input in inputs {
+            if let Some(action) =
+                self.find_action(input, modifiers, terminal_mode)
+            {
                 return action.clone();
+            }
+        }
+
+        // Explicit bindings take precedence, the rest of the modified keys
+        // are encoded the same way xterm does it
+        inputs
+            .first()
+            .and_then(|input| {
+                xterm_key_sequence(input, modifiers, self.modify_other_keys)
+            })
+            .map_or(BindingAction::Ignore, BindingAction::Esc)
+    }
+
+    fn find_action(
+        &self,
+        input: &InputKind,
+        modifiers: Modifiers,
+        terminal_mode: TermMode,
+    ) -> Option<&BindingAction> {
+        let bucket = self.index.get(input)?.get(&modifiers)?;
+        self.first_active(bucket, terminal_mode)
+            .map(|position| &self.layout[position].1)
+    }
+}
+
+/// Returns the xterm parameter for the modifiers
+/// (1 + Shift(1) + Alt(2) + Ctrl(4)) or `None` if there is nothing to encode.
+fn xterm_modifier_param(modifiers: Modifiers) -> Option<u8> {
+    if modifiers.logo() {
+        return None;
+    }
+
+    let mut param = 1;
+    if modifiers.shift() {
+        param += 1;
+    }
+    if modifiers.alt() {
+        param += 2;
+    }
+    if modifiers.control() {
+        param += 4;
+    }
+
+    (param > 1).then_some(param)
+}
+
+/// Builds the xterm sequence for the modified key:
+/// `CSI 1;<mod><final>` for cursor keys and F1-F4,
+/// `CSI <n>;<mod>~` for the editing keypad and F5-F20 and,
+/// if `modifyOtherKeys` level 2 is enabled,
+/// `CSI 27;<mod>;<code>~` for the rest of the keys.
+fn xterm_key_sequence(
+    input: &InputKind,
+    modifiers: Modifiers,
+    modify_other_keys: bool,
+) -> Option<String> {
+    let param = xterm_modifier_param(modifiers)?;
+
+    let code = match input {
+        InputKind::KeyCode(key) => {
+            let final_char = match key {
+                Named::ArrowUp => Some('A'),
+                Named::ArrowDown => Some('B'),
+                Named::ArrowRight => Some('C'),
+                Named::ArrowLeft => Some('D'),
+                Named::End => Some('F'),
+                Named::Home => Some('H'),
+                Named::F1 => Some('P'),
+                Named::F2 => Some('Q'),
+                Named::F3 => Some('R'),
+                Named::F4 => Some('S'),
+                _ => None,
+            };
+            if let Some(c) = final_char {
+                return Some(format!("\x1b[1;{}{}", param, c));
+            }
+
+            let number = match key {
+                Named::Insert => Some(2),
+                Named::Delete => Some(3),
+                Named::PageUp => Some(5),
+                Named::PageDown => Some(6),
+                Named::F5 => Some(15),
+                Named::F6 => Some(17),
+                Named::F7 => Some(18),
+                Named::F8 => Some(19),
+                Named::F9 => Some(20),
+                Named::F10 => Some(21),
+                Named::F11 => Some(23),
+                Named::F12 => Some(24),
+                Named::F13 => Some(25),
+                Named::F14 => Some(26),
+                Named::F15 => Some(28),
+                Named::F16 => Some(29),
+                Named::F17 => Some(31),
+                Named::F18 => Some(32),
+                Named::F19 => Some(33),
+                Named::F20 => Some(34),
+                _ => None,
+            };
+            if let Some(n) = number {
+                return Some(format!("\x1b[{};{}~", n, param));
+            }
+
+            match key {
+                Named::Space => ' ' as u32,
+                Named::Enter => '\x0d' as u32,
+                Named::Tab => '\x09' as u32,
+                Named::Backspace => '\x7f' as u32,
+                Named::Escape => '\x1b' as u32,
+                _ => return None,
+            }
+        },
+        InputKind::Char(c) => {
+            let mut chars = c.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c as u32,
+                _ => return None,
+            }
+        },
+        InputKind::Physical(_) | InputKind::Mouse(_) => return None,
+    };
+
+    // Shifted printable characters are sent as text even for level 2
+    let is_shifted_char =
+        matches!(input, InputKind::Char(_)) && modifiers == Modifiers::SHIFT;
+    if !modify_other_keys || is_shifted_char {
+        return None;
+    }
+
+    Some(format!("\x1b[27;{};{}~", param, code))
+}
+
+const NAMED_KEYS: &[(&str, Named)] = &[
+    ("Space", Named::Space),
+    ("Enter", Named::Enter),
+    ("Backspace", Named::Backspace),
+    ("Escape", Named::Escape),
+    ("Tab", Named::Tab),
+    ("Insert", Named::Insert),
+    ("Delete", Named::Delete),
+    ("PageUp", Named::PageUp),
+    ("PageDown", Named::PageDown),
+    ("Home", Named::Home),
+    ("End", Named::End),
+    ("Up", Named::ArrowUp),
+    ("Down", Named::ArrowDown),
+    ("Left", Named::ArrowLeft),
+    ("Right", Named::ArrowRight),
+    ("F1", Named::F1),
+    ("F2", Named::F2),
+    ("F3", Named::F3),
+    ("F4", Named::F4),
+    ("F5", Named::F5),
+    ("F6", Named::F6),
+    ("F7", Named::F7),
+    ("F8", Named::F8),
+    ("F9", Named::F9),
+    ("F10", Named::F10),
+    ("F11", Named::F11),
+    ("F12", Named::F12),
+    ("F13", Named::F13),
+    ("F14", Named::F14),
+    ("F15", Named::F15),
+    ("F16", Named::F16),
+    ("F17", Named::F17),
+    ("F18", Named::F18),
+    ("F19", Named::F19),
+    ("F20", Named::F20),
+    // Aliases, only used for parsing
+    ("ArrowUp", Named::ArrowUp),
+    ("ArrowDown", Named::ArrowDown),
+    ("ArrowLeft", Named::ArrowLeft),
+    ("ArrowRight", Named::ArrowRight),
+    ("Esc", Named::Escape),
+    ("Return", Named::Enter),
+];
+
+const PHYSICAL_KEYS: &[(&str, Code)] = &[
+    ("KeyA", Code::KeyA),
+    ("KeyB", Code::KeyB),
+    ("KeyC", Code::KeyC),
+    ("KeyD", Code::KeyD),
+    ("KeyE", Code::KeyE),
+    ("KeyF", Code::KeyF),
+    ("KeyG", Code::KeyG),
+    ("KeyH", Code::KeyH),
+    ("KeyI", Code::KeyI),
+    ("KeyJ", Code::KeyJ),
+    ("KeyK", Code::KeyK),
+    ("KeyL", Code::KeyL),
+    ("KeyM", Code::KeyM),
+    ("KeyN", Code::KeyN),
+    ("KeyO", Code::KeyO),
+    ("KeyP", Code::KeyP),
+    ("KeyQ", Code::KeyQ),
+    ("KeyR", Code::KeyR),
+    ("KeyS", Code::KeyS),
+    ("KeyT", Code::KeyT),
+    ("KeyU", Code::KeyU),
+    ("KeyV", Code::KeyV),
+    ("KeyW", Code::KeyW),
+    ("KeyX", Code::KeyX),
+    ("KeyY", Code::KeyY),
+    ("KeyZ", Code::KeyZ),
+    ("Digit0", Code::Digit0),
+    ("Digit1", Code::Digit1),
+    ("Digit2", Code::Digit2),
+    ("Digit3", Code::Digit3),
+    ("Digit4", Code::Digit4),
+    ("Digit5", Code::Digit5),
+    ("Digit6", Code::Digit6),
+    ("Digit7", Code::Digit7),
+    ("Digit8", Code::Digit8),
+    ("Digit9", Code::Digit9),
+    ("Minus", Code::Minus),
+    ("Equal", Code::Equal),
+    ("BracketLeft", Code::BracketLeft),
+    ("BracketRight", Code::BracketRight),
+    ("Backslash", Code::Backslash),
+    ("Semicolon", Code::Semicolon),
+    ("Quote", Code::Quote),
+    ("Backquote", Code::Backquote),
+    ("Comma", Code::Comma),
+    ("Period", Code::Period),
+    ("Slash", Code::Slash),
+];
+
+const MOUSE_BUTTONS: &[(&str, Button)] = &[
+    ("Left", Button::Left),
+    ("Right", Button::Right),
+    ("Middle", Button::Middle),
+    ("Back", Button::Back),
+    ("Forward", Button::Forward),
+];
+
+const PHYSICAL_PREFIX: &str = "physical:";
+const MOUSE_PREFIX: &str = "mouse:";
+
+fn find_by_name<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value)
+}
+
+/// The error of parsing a binding from its text form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingParseError {
+    pub entry: String,
+    pub reason: String,
+}
+
+impl BindingParseError {
+    fn new(entry: &str, reason: impl Into<String>) -> Self {
+        Self {
+            entry: entry.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for BindingParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid binding \"{}\": {}", self.entry, self.reason)
+    }
+}
+
+impl std::error::Error for BindingParseError {}
+
+impl fmt::Display for InputKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputKind::Char(c) => write!(f, "{}", c),
+            InputKind::KeyCode(key) => {
+                match NAMED_KEYS.iter().find(|(_, named)| named == key) {
+                    Some((name, _)) => write!(f, "{}", name),
+                    None => write!(f, "{:?}", key),
+                }
+            },
+            InputKind::Physical(code) => {
+                write!(f, "{}{:?}", PHYSICAL_PREFIX, code)
+            },
+            InputKind::Mouse(button) => {
+                write!(f, "{}{:?}", MOUSE_PREFIX, button)
+            },
+        }
+    }
+}
+
+impl FromStr for InputKind {
+    type Err = BindingParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = s.strip_prefix(PHYSICAL_PREFIX) {
+            return find_by_name(PHYSICAL_KEYS, name)
+                .map(InputKind::Physical)
+                .ok_or_else(|| {
+                    BindingParseError::new(
+                        s,
+                        format!("unknown physical key \"{}\"", name),
+                    )
+                });
+        }
+
+        if let Some(name) = s.strip_prefix(MOUSE_PREFIX) {
+            return find_by_name(MOUSE_BUTTONS, name)
+                .map(InputKind::Mouse)
+                .ok_or_else(|| {
+                    BindingParseError::new(
+                        s,
+                        format!("unknown mouse button \"{}\"", name),
+                    )
+                });
+        }
+
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                Ok(InputKind::Char(c.to_lowercase().to_string()))
+            },
+            (Some(_), Some(_)) => find_by_name(NAMED_KEYS, s)
+                .map(InputKind::KeyCode)
+                .ok_or_else(|| {
+                    BindingParseError::new(s, format!("unknown key \"{}\"", s))
+                }),
+            _ => Err(BindingParseError::new(s, "the key is missing")),
+        }
+    }
+}
+
+/// The text form of the binding is `mod+mod+key [+MODE] [~MODE]`, e.g.
+/// `ctrl+shift+c`, `alt+Left` or `shift+PageUp +ALT_SCREEN ~VI`.
+/// Modes are the names of [`TermMode`] flags that must be set (`+`)
+/// or unset (`~`) for the binding to trigger.
+impl fmt::Display for Binding<InputKind> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.control() {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.alt() {
+            write!(f, "alt+")?;
+        }
+        if self.modifiers.shift() {
+            write!(f, "shift+")?;
+        }
+        if self.modifiers.logo() {
+            write!(f, "super+")?;
+        }
+        write!(f, "{}", self.target)?;
+        for (name, _) in self.terminal_mode_include.iter_names() {
+            write!(f, " +{}", name)?;
+        }
+        for (name, _) in self.terminal_mode_exclude.iter_names() {
+            write!(f, " ~{}", name)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ChordKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.inputs.first() {
+            Some(input) => Binding {
+                target: input.clone(),
+                modifiers: self.modifiers,
+                terminal_mode_include: TermMode::empty(),
+                terminal_mode_exclude: TermMode::empty(),
+            }
+            .fmt(f),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromStr for Binding<InputKind> {
+    type Err = BindingParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace();
+        let chord = tokens
+            .next()
+            .ok_or_else(|| BindingParseError::new(s, "the binding is empty"))?;
+
+        // The last `+` of the chord separates the key,
+        // so `ctrl++` is Ctrl with the plus key
+        let (modifier_names, key) = match chord.strip_suffix("++") {
+            Some(modifier_names) => (modifier_names, "+"),
+            None if chord == "+" => ("", "+"),
+            None => match chord.rsplit_once('+') {
+                Some((modifier_names, key)) => (modifier_names, key),
+                None => ("", chord),
+            },
+        };
+
+        let mut modifiers = Modifiers::empty();
+        for name in modifier_names.split('+').filter(|name| !name.is_empty()) {
+            modifiers |= match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => Modifiers::CTRL,
+                "shift" => Modifiers::SHIFT,
+                "alt" | "option" => Modifiers::ALT,
+                "super" | "logo" | "meta" => Modifiers::LOGO,
+                "cmd" | "command" => Modifiers::COMMAND,
+                _ => {
+                    return Err(BindingParseError::new(
+                        s,
+                        format!("unknown modifier \"{}\"", name),
+                    ))
+                },
+            };
+        }
+
+        let target = key
+            .parse::<InputKind>()
+            .map_err(|e| BindingParseError::new(s, e.reason))?;
+
+        let mut terminal_mode_include = TermMode::empty();
+        let mut terminal_mode_exclude = TermMode::empty();
+        for token in tokens {
+            let (modes, name) = if let Some(name) = token.strip_prefix('+') {
+                (&mut terminal_mode_include, name)
+            } else if let Some(name) = token.strip_prefix('~') {
+                (&mut terminal_mode_exclude, name)
+            } else {
+                return Err(BindingParseError::new(
+                    s,
+                    format!("the mode \"{}\" must start with + or ~", token),
+                ));
             };
+
+            let mode = TermMode::from_name(&name.to_ascii_uppercase())
+                .ok_or_else(|| {
+                    BindingParseError::new(
+                        s,
+                        format!("unknown terminal mode \"{}\"", name),
+                    )
+                })?;
+            modes.insert(mode);
         }
 
-        BindingAction::Ignore
+        Ok(Binding {
+            target,
+            modifiers,
+            terminal_mode_include,
+            terminal_mode_exclude,
+        })
+    }
+}
+
+impl Serialize for InputKind {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for InputKind {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Binding<InputKind> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Binding<InputKind> {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Bindings in the text form, e.g. in TOML
+///
+/// ```toml
+/// "ctrl+shift+c" = "Copy"
+/// "alt+Left" = { esc = "\u001bb" }
+/// "shift+PageUp +ALT_SCREEN" = { esc = "\u001b[5;2~" }
+/// ```
+///
+/// The entries keep the order the format delivers them in (e.g. `toml` needs
+/// the `preserve_order` feature), so the first matching one wins the same
+/// way it does for [`generate_bindings!`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BindingsConfig(pub Vec<(String, BindingAction)>);
+
+impl BindingsConfig {
+    pub fn parse(
+        &self,
+    ) -> Result<Vec<(Binding<InputKind>, BindingAction)>, BindingParseError>
+    {
+        self.0
+            .iter()
+            .map(|(binding, action)| Ok((binding.parse()?, action.clone())))
+            .collect()
+    }
+}
+
+impl FromIterator<(Binding<InputKind>, BindingAction)> for BindingsConfig {
+    fn from_iter<
+        I: IntoIterator<Item = (Binding<InputKind>, BindingAction)>,
+    >(
+        iter: I,
+    ) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(binding, action)| (binding.to_string(), action))
+                .collect(),
+        )
+    }
+}
+
+impl Serialize for BindingsConfig {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (binding, action) in &self.0 {
+            map.serialize_entry(binding, action)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for BindingsConfig {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        struct BindingsConfigVisitor;
+
+        impl<'de> Visitor<'de> for BindingsConfigVisitor {
+            type Value = BindingsConfig;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a map of bindings to actions")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut entries =
+                    Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(binding) = map.next_key::<String>()? {
+                    let action =
+                        map.next_value::<BindingAction>().map_err(|e| {
+                            de::Error::custom(format!(
+                                "invalid action of binding \"{}\": {}",
+                                binding, e
+                            ))
+                        })?;
+                    entries.push((binding, action));
+                }
+
+                Ok(BindingsConfig(entries))
+            }
+        }
+
+        deserializer.deserialize_map(BindingsConfigVisitor)
     }
 }
 
@@ -187,27 +967,6 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         ArrowLeft,  +TermMode::APP_CURSOR; BindingAction::Esc("\x1bOD".into());
         ArrowRight, +TermMode::APP_CURSOR; BindingAction::Esc("\x1bOC".into());
         // CTRL
-        ArrowUp,    Modifiers::COMMAND; BindingAction::Esc("\x1b[1;5A".into());
-        ArrowDown,  Modifiers::COMMAND; BindingAction::Esc("\x1b[1;5B".into());
-        ArrowLeft,  Modifiers::COMMAND; BindingAction::Esc("\x1b[1;5D".into());
-        ArrowRight, Modifiers::COMMAND; BindingAction::Esc("\x1b[1;5C".into());
-        End,        Modifiers::CTRL; BindingAction::Esc("\x1b[1;5F".into());
-        Home,       Modifiers::CTRL; BindingAction::Esc("\x1b[1;5H".into());
-        Delete,     Modifiers::CTRL; BindingAction::Esc("\x1b[3;5~".into());
-        PageUp,     Modifiers::CTRL; BindingAction::Esc("\x1b[5;5~".into());
-        PageDown,   Modifiers::CTRL; BindingAction::Esc("\x1b[6;5~".into());
-        F1,         Modifiers::CTRL; BindingAction::Esc("\x1bO;5P".into());
-        F2,         Modifiers::CTRL; BindingAction::Esc("\x1bO;5Q".into());
-        F3,         Modifiers::CTRL; BindingAction::Esc("\x1bO;5R".into());
-        F4,         Modifiers::CTRL; BindingAction::Esc("\x1bO;5S".into());
-        F5,         Modifiers::CTRL; BindingAction::Esc("\x1b[15;5~".into());
-        F6,         Modifiers::CTRL; BindingAction::Esc("\x1b[17;5~".into());
-        F7,         Modifiers::CTRL; BindingAction::Esc("\x1b[18;5~".into());
-        F8,         Modifiers::CTRL; BindingAction::Esc("\x1b[19;5~".into());
-        F9,         Modifiers::CTRL; BindingAction::Esc("\x1b[20;5~".into());
-        F10,        Modifiers::CTRL; BindingAction::Esc("\x1b[21;5~".into());
-        F11,        Modifiers::CTRL; BindingAction::Esc("\x1b[23;5~".into());
-        F12,        Modifiers::CTRL; BindingAction::Esc("\x1b[24;5~".into());
         "a",        Modifiers::CTRL; BindingAction::Char('\x01');
         "b",        Modifiers::CTRL; BindingAction::Char('\x02');
         "c",        Modifiers::CTRL; BindingAction::Char('\x03');
@@ -228,7 +987,7 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         "r",        Modifiers::CTRL; BindingAction::Char('\x12');
         "s",        Modifiers::CTRL; BindingAction::Char('\x13');
         "t",        Modifiers::CTRL; BindingAction::Char('\x14');
-        "u",        Modifiers::CTRL; BindingAction::Char('\x51');
+        "u",        Modifiers::CTRL; BindingAction::Char('\x15');
         "v",        Modifiers::CTRL; BindingAction::Char('\x16');
         "w",        Modifiers::CTRL; BindingAction::Char('\x17');
         "x",        Modifiers::CTRL; BindingAction::Char('\x18');
@@ -242,40 +1001,14 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         Enter,      Modifiers::SHIFT; BindingAction::Char('\x0d');
         Backspace,  Modifiers::SHIFT; BindingAction::Char('\x7f');
         Tab,        Modifiers::SHIFT; BindingAction::Esc("\x1b[Z".into());
-        End,        Modifiers::SHIFT, +TermMode::ALT_SCREEN; BindingAction::Esc("\x1b[1;2F".into());
-        Home,       Modifiers::SHIFT, +TermMode::ALT_SCREEN; BindingAction::Esc("\x1b[1;2H".into());
-        PageUp,     Modifiers::SHIFT, +TermMode::ALT_SCREEN; BindingAction::Esc("\x1b[5;2~".into());
-        PageDown,   Modifiers::SHIFT, +TermMode::ALT_SCREEN; BindingAction::Esc("\x1b[6;2~".into());
-        ArrowUp,    Modifiers::SHIFT; BindingAction::Esc("\x1b[1;2A".into());
-        ArrowDown,  Modifiers::SHIFT; BindingAction::Esc("\x1b[1;2B".into());
-        ArrowLeft,  Modifiers::SHIFT; BindingAction::Esc("\x1b[1;2D".into());
-        ArrowRight, Modifiers::SHIFT; BindingAction::Esc("\x1b[1;2C".into());
+        // Scrolling of the history, the alt screen gets the sequences
+        PageUp,     Modifiers::SHIFT, ~TermMode::ALT_SCREEN; BindingAction::ScrollPageUp;
+        PageDown,   Modifiers::SHIFT, ~TermMode::ALT_SCREEN; BindingAction::ScrollPageDown;
+        Home,       Modifiers::SHIFT, ~TermMode::ALT_SCREEN; BindingAction::ScrollToTop;
+        End,        Modifiers::SHIFT, ~TermMode::ALT_SCREEN; BindingAction::ScrollToBottom;
         // ALT
         Backspace,  Modifiers::ALT; BindingAction::Esc("\x1b\x7f".into());
-        End,        Modifiers::ALT; BindingAction::Esc("\x1b[1;3F".into());
-        Home,       Modifiers::ALT; BindingAction::Esc("\x1b[1;3H".into());
-        Insert,     Modifiers::ALT; BindingAction::Esc("\x1b[3;2~".into());
-        Delete,     Modifiers::ALT; BindingAction::Esc("\x1b[3;3~".into());
-        PageUp,     Modifiers::ALT; BindingAction::Esc("\x1b[5;3~".into());
-        PageDown,   Modifiers::ALT; BindingAction::Esc("\x1b[6;3~".into());
-        ArrowUp,    Modifiers::ALT; BindingAction::Esc("\x1b[1;3A".into());
-        ArrowDown,  Modifiers::ALT; BindingAction::Esc("\x1b[1;3B".into());
-        ArrowLeft,  Modifiers::ALT; BindingAction::Esc("\x1b[1;3D".into());
-        ArrowRight, Modifiers::ALT; BindingAction::Esc("\x1b[1;3C".into());
-        // SHIFT + ALT
-        End,        Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4F".into());
-        Home,       Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4H".into());
-        ArrowUp,    Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4A".into());
-        ArrowDown,  Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4B".into());
-        ArrowLeft,  Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4D".into());
-        ArrowRight, Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4C".into());
         // SHIFT + CTRL
-        End,        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6F".into());
-        Home,       Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6H".into());
-        ArrowUp,    Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6A".into());
-        ArrowDown,  Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6B".into());
-        ArrowLeft,  Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6D".into());
-        ArrowRight, Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6C".into());
         "a",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x01');
         "b",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x02');
         "c",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x03');
@@ -296,7 +1029,7 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         "r",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x12');
         "s",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x13');
         "t",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x14');
-        "u",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x51');
+        "u",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x15');
         "v",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x16');
         "w",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x17');
         "x",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x18');
@@ -305,22 +1038,6 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         "2",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x00'); // Null vt100
         "6",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x1e');
         "_",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x1f');
-        // CTRL + ALT
-        End,        Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7F".into());
-        Home,       Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7H".into());
-        PageUp,     Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[5;7~".into());
-        PageDown,   Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[6;7~".into());
-        ArrowUp,    Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7A".into());
-        ArrowDown,  Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7B".into());
-        ArrowLeft,  Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7D".into());
-        ArrowRight, Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7C".into());
-        // SHIFT + CTRL + ALT
-        End,        Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8F".into());
-        Home,       Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8H".into());
-        ArrowUp,    Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8A".into());
-        ArrowDown,  Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8B".into());
-        ArrowLeft,  Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8D".into());
-        ArrowRight, Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8C".into());
     )
 }
 
@@ -328,9 +1045,19 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
 fn platform_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
     generate_bindings!(
         KeyboardBinding;
+        ArrowUp,    Modifiers::COMMAND; BindingAction::Esc("\x1b[1;5A".into());
+        ArrowDown,  Modifiers::COMMAND; BindingAction::Esc("\x1b[1;5B".into());
+        ArrowLeft,  Modifiers::COMMAND; BindingAction::Esc("\x1b[1;5D".into());
+        ArrowRight, Modifiers::COMMAND; BindingAction::Esc("\x1b[1;5C".into());
         "c", Modifiers::COMMAND; BindingAction::Copy;
         "v", Modifiers::COMMAND; BindingAction::Paste;
         "h", Modifiers::COMMAND; BindingAction::Char('\x7f');
+        "=", Modifiers::COMMAND; BindingAction::IncreaseFontSize;
+        "+", Modifiers::COMMAND; BindingAction::IncreaseFontSize;
+        "-", Modifiers::COMMAND; BindingAction::DecreaseFontSize;
+        "0", Modifiers::COMMAND; BindingAction::ResetFontSize;
+        "k", Modifiers::COMMAND; BindingAction::ClearHistory;
+        "a", Modifiers::COMMAND; BindingAction::SelectAll;
     )
 }
 
@@ -354,10 +1081,18 @@ fn mouse_default_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
 mod tests {
     use crate::bindings::MouseBinding;
 
-    use super::{BindingAction, BindingsLayout, InputKind, KeyboardBinding};
+    use super::{
+        default_keyboard_bindings, mouse_default_bindings,
+        platform_keyboard_bindings, Binding, BindingAction, BindingsConfig,
+        BindingsLayout, ChordKey, ChordMatch, InputKind, KeyboardBinding,
+        KeyboardChord,
+    };
     use alacritty_terminal::term::TermMode;
     use iced_core::{
-        keyboard::{key::Named, Modifiers},
+        keyboard::{
+            key::{Code, Named},
+            Modifiers,
+        },
         mouse::Button,
     };
 
@@ -497,4 +1232,556 @@ mod tests {
             assert_eq!(action, &found_action);
         }
     }
+
+    #[test]
+    fn get_action_for_modified_named_keys() {
+        let current_layout = BindingsLayout::default();
+        let cases = vec![
+            (Named::F1, Modifiers::SHIFT, TermMode::empty(), "\x1b[1;2P"),
+            (
+                Named::F12,
+                Modifiers::SHIFT,
+                TermMode::empty(),
+                "\x1b[24;2~",
+            ),
+            (Named::F5, Modifiers::ALT, TermMode::empty(), "\x1b[15;3~"),
+            (Named::F4, Modifiers::CTRL, TermMode::empty(), "\x1b[1;5S"),
+            (
+                Named::Insert,
+                Modifiers::CTRL,
+                TermMode::empty(),
+                "\x1b[2;5~",
+            ),
+            (
+                Named::Insert,
+                Modifiers::ALT,
+                TermMode::empty(),
+                "\x1b[2;3~",
+            ),
+            (
+                Named::Delete,
+                Modifiers::CTRL | Modifiers::ALT,
+                TermMode::empty(),
+                "\x1b[3;7~",
+            ),
+            (
+                Named::Home,
+                Modifiers::CTRL | Modifiers::SHIFT,
+                TermMode::empty(),
+                "\x1b[1;6H",
+            ),
+            (
+                Named::ArrowLeft,
+                Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT,
+                TermMode::APP_CURSOR,
+                "\x1b[1;8D",
+            ),
+            (
+                Named::PageUp,
+                Modifiers::SHIFT,
+                TermMode::ALT_SCREEN,
+                "\x1b[5;2~",
+            ),
+        ];
+
+        for (key, modifiers, terminal_mode, expected) in cases {
+            let found_action = current_layout.get_action(
+                InputKind::KeyCode(key),
+                modifiers,
+                terminal_mode,
+            );
+            assert_eq!(found_action, BindingAction::Esc(expected.into()));
+        }
+    }
+
+    #[test]
+    fn get_action_for_ctrl_u() {
+        let current_layout = BindingsLayout::default();
+        let found_action = current_layout.get_action(
+            InputKind::Char(String::from("u")),
+            Modifiers::CTRL,
+            TermMode::empty(),
+        );
+        assert_eq!(found_action, BindingAction::Char('\x15'));
+    }
+
+    #[test]
+    fn get_action_for_shift_page_up() {
+        let current_layout = BindingsLayout::default();
+        let found_action = current_layout.get_action(
+            InputKind::KeyCode(Named::PageUp),
+            Modifiers::SHIFT,
+            TermMode::empty(),
+        );
+        assert_eq!(found_action, BindingAction::ScrollPageUp);
+
+        // The alt screen has no history, the application gets the key
+        let found_action = current_layout.get_action(
+            InputKind::KeyCode(Named::PageUp),
+            Modifiers::SHIFT,
+            TermMode::ALT_SCREEN,
+        );
+        assert_eq!(found_action, BindingAction::Esc("\x1b[5;2~".into()));
+    }
+
+    #[test]
+    fn remove_default_binding() {
+        let mut current_layout = BindingsLayout::default();
+        let copy_binding: KeyboardBinding = "ctrl+shift+c".parse().unwrap();
+        current_layout.remove_bindings(vec![copy_binding.clone()]);
+        let found_action = current_layout.get_action(
+            copy_binding.target,
+            copy_binding.modifiers,
+            TermMode::empty(),
+        );
+        assert_eq!(found_action, BindingAction::Ignore);
+    }
+
+    #[test]
+    fn match_chord_bindings() {
+        let mut current_layout = BindingsLayout::default();
+        let chord = |keys: &[&str]| -> KeyboardChord {
+            keys.iter().map(|key| key.parse().unwrap()).collect()
+        };
+        let key = |key: &str| -> ChordKey {
+            let binding: KeyboardBinding = key.parse().unwrap();
+            ChordKey {
+                inputs: vec![binding.target],
+                modifiers: binding.modifiers,
+            }
+        };
+        current_layout.add_chord_bindings(vec![
+            (chord(&["ctrl+a", "c"]), BindingAction::Custom("new".into())),
+            (chord(&["ctrl+a", "x", "y"]), BindingAction::ClearHistory),
+            (
+                chord(&["ctrl+b +ALT_SCREEN", "c"]),
+                BindingAction::SelectAll,
+            ),
+        ]);
+
+        let cases = vec![
+            (vec![], "ctrl+a", ChordMatch::Prefix),
+            (vec![], "a", ChordMatch::Mismatch),
+            (
+                vec![key("ctrl+a")],
+                "c",
+                ChordMatch::Complete(BindingAction::Custom("new".into())),
+            ),
+            (vec![key("ctrl+a")], "x", ChordMatch::Prefix),
+            (vec![key("ctrl+a")], "shift+c", ChordMatch::Mismatch),
+            (
+                vec![key("ctrl+a"), key("x")],
+                "y",
+                ChordMatch::Complete(BindingAction::ClearHistory),
+            ),
+            (vec![], "ctrl+b", ChordMatch::Mismatch),
+        ];
+        for (pending, pressed, expected) in cases {
+            assert_eq!(
+                current_layout.match_chord(
+                    &pending,
+                    &key(pressed),
+                    TermMode::empty()
+                ),
+                expected,
+                "{:?} {}",
+                pending,
+                pressed
+            );
+        }
+
+        current_layout.remove_chord_bindings(vec![chord(&["ctrl+a", "c"])]);
+        assert_eq!(
+            current_layout.match_chord(
+                &[key("ctrl+a")],
+                &key("c"),
+                TermMode::empty()
+            ),
+            ChordMatch::Mismatch
+        );
+    }
+
+    /// The first match scan over the whole layout
+    /// the indexed lookup has to be equivalent to
+    struct LinearLayout(Vec<(KeyboardBinding, BindingAction)>);
+
+    impl LinearLayout {
+        fn new() -> Self {
+            let mut layout = Self(default_keyboard_bindings());
+            layout.add_bindings(platform_keyboard_bindings());
+            layout.add_bindings(mouse_default_bindings());
+            layout
+        }
+
+        fn add_bindings(
+            &mut self,
+            bindings: Vec<(KeyboardBinding, BindingAction)>,
+        ) {
+            for (binding, action) in bindings {
+                match self.0.iter().position(|(b, _)| b == &binding) {
+                    Some(position) => self.0[position] = (binding, action),
+                    None => self.0.push((binding, action)),
+                }
+            }
+        }
+
+        fn remove_bindings(&mut self, bindings: Vec<KeyboardBinding>) {
+            self.0.retain(|(binding, _)| !bindings.contains(binding));
+        }
+
+        fn find_action(
+            &self,
+            input: &InputKind,
+            modifiers: Modifiers,
+            terminal_mode: TermMode,
+        ) -> Option<&BindingAction> {
+            self.0
+                .iter()
+                .find(|(binding, _)| {
+                    &binding.target == input
+                        && binding.modifiers == modifiers
+                        && terminal_mode.contains(binding.terminal_mode_include)
+                        && !terminal_mode
+                            .intersects(binding.terminal_mode_exclude)
+                })
+                .map(|(_, action)| action)
+        }
+    }
+
+    fn assert_same_lookup(indexed: &BindingsLayout, linear: &LinearLayout) {
+        let mut inputs: Vec<InputKind> = linear
+            .0
+            .iter()
+            .map(|(binding, _)| binding.target.clone())
+            .collect();
+        inputs.push(InputKind::Char(String::from("ы")));
+        inputs.push(InputKind::KeyCode(Named::F24));
+        inputs.push(InputKind::Physical(Code::KeyQ));
+        let modifier_flags = [
+            Modifiers::SHIFT,
+            Modifiers::CTRL,
+            Modifiers::ALT,
+            Modifiers::LOGO,
+        ];
+        let modes = [
+            TermMode::empty(),
+            TermMode::APP_CURSOR,
+            TermMode::APP_KEYPAD,
+            TermMode::ALT_SCREEN,
+            TermMode::SGR_MOUSE,
+            TermMode::APP_CURSOR | TermMode::ALT_SCREEN,
+            TermMode::APP_KEYPAD | TermMode::ALT_SCREEN,
+            TermMode::SGR_MOUSE | TermMode::ALT_SCREEN,
+            TermMode::all(),
+        ];
+
+        for input in &inputs {
+            for bits in 0..16 {
+                let modifiers = modifier_flags
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| bits & (1 << i) != 0)
+                    .fold(Modifiers::empty(), |acc, (_, m)| acc | *m);
+                for mode in modes {
+                    assert_eq!(
+                        indexed.find_action(input, modifiers, mode),
+                        linear.find_action(input, modifiers, mode),
+                        "{:?} {:?} {:?}",
+                        input,
+                        modifiers,
+                        mode
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn indexed_lookup_matches_linear_scan() {
+        let mut indexed = BindingsLayout::new();
+        let mut linear = LinearLayout::new();
+        assert_same_lookup(&indexed, &linear);
+
+        // Overlapping mode filters, replaced and brand new bindings
+        let custom_bindings = generate_bindings!(
+            KeyboardBinding;
+            ArrowUp, +TermMode::ALT_SCREEN; BindingAction::Esc("\x1bOA".into());
+            ArrowUp, Modifiers::SHIFT, +TermMode::APP_CURSOR, ~TermMode::ALT_SCREEN; BindingAction::ScrollPageUp;
+            "c", Modifiers::CTRL | Modifiers::SHIFT; BindingAction::Paste;
+            "c", Modifiers::CTRL | Modifiers::SHIFT, +TermMode::ALT_SCREEN; BindingAction::Copy;
+            Code::KeyQ, Modifiers::LOGO; BindingAction::Custom("quit".into());
+            F24; BindingAction::Ignore;
+        );
+        indexed.add_bindings(custom_bindings.clone());
+        linear.add_bindings(custom_bindings);
+        assert_same_lookup(&indexed, &linear);
+
+        let removed_bindings: Vec<KeyboardBinding> = vec![
+            "ctrl+shift+c".parse().unwrap(),
+            "Up +APP_CURSOR".parse().unwrap(),
+            "shift+PageUp ~ALT_SCREEN".parse().unwrap(),
+            "ctrl+c".parse().unwrap(),
+        ];
+        indexed.remove_bindings(removed_bindings.clone());
+        linear.remove_bindings(removed_bindings);
+        assert_same_lookup(&indexed, &linear);
+
+        // Added after the removal the positions have to be shifted
+        let custom_bindings = generate_bindings!(
+            KeyboardBinding;
+            "c", Modifiers::CTRL; BindingAction::Char('\x03');
+            PageUp, Modifiers::SHIFT; BindingAction::ScrollToTop;
+        );
+        indexed.add_bindings(custom_bindings.clone());
+        linear.add_bindings(custom_bindings);
+        assert_same_lookup(&indexed, &linear);
+    }
+
+    #[test]
+    fn list_active_bindings() {
+        let layout = BindingsLayout::new();
+        for mode in [TermMode::empty(), TermMode::ALT_SCREEN] {
+            let active_bindings = layout.active_bindings(mode);
+            for (i, (binding, action)) in active_bindings.iter().enumerate() {
+                assert_ne!(action, &BindingAction::Ignore);
+                assert_eq!(
+                    layout.find_action(
+                        &binding.target,
+                        binding.modifiers,
+                        mode
+                    ),
+                    Some(action)
+                );
+                // One entry per key
+                assert!(!active_bindings[i + 1..].iter().any(|(other, _)| {
+                    other.target == binding.target
+                        && other.modifiers == binding.modifiers
+                }));
+            }
+        }
+
+        let page_up: KeyboardBinding = "shift+PageUp".parse().unwrap();
+        let action_in = |mode| {
+            layout
+                .active_bindings(mode)
+                .into_iter()
+                .find(|(binding, _)| {
+                    binding.target == page_up.target
+                        && binding.modifiers == page_up.modifiers
+                })
+                .map(|(_, action)| action.clone())
+        };
+        assert_eq!(
+            action_in(TermMode::empty()),
+            Some(BindingAction::ScrollPageUp)
+        );
+        // The xterm sequence is computed, there is no explicit binding
+        assert_eq!(action_in(TermMode::ALT_SCREEN), None);
+    }
+
+    #[test]
+    fn custom_binding_overrides_computed_sequence() {
+        let mut current_layout = BindingsLayout::default();
+        let custom_bindings = generate_bindings!(
+            KeyboardBinding;
+            F1, Modifiers::SHIFT; BindingAction::Esc("\x1b[23~".into());
+        );
+        current_layout.add_bindings(custom_bindings);
+        let found_action = current_layout.get_action(
+            InputKind::KeyCode(Named::F1),
+            Modifiers::SHIFT,
+            TermMode::empty(),
+        );
+        assert_eq!(found_action, BindingAction::Esc("\x1b[23~".into()));
+    }
+
+    #[test]
+    fn get_action_with_modify_other_keys() {
+        let mut current_layout = BindingsLayout::default();
+        let cases = vec![
+            (InputKind::Char(String::from("1")), Modifiers::CTRL),
+            (InputKind::Char(String::from("a")), Modifiers::ALT),
+            (InputKind::Char(String::from("a")), Modifiers::SHIFT),
+            (InputKind::KeyCode(Named::Enter), Modifiers::CTRL),
+        ];
+        for (input, modifiers) in cases.clone() {
+            let found_action =
+                current_layout.get_action(input, modifiers, TermMode::empty());
+            assert_eq!(found_action, BindingAction::Ignore);
+        }
+
+        current_layout.modify_other_keys = true;
+        let expected = vec![
+            BindingAction::Esc("\x1b[27;5;49~".into()),
+            BindingAction::Esc("\x1b[27;3;97~".into()),
+            BindingAction::Ignore,
+            BindingAction::Esc("\x1b[27;5;13~".into()),
+        ];
+        for ((input, modifiers), expected) in cases.into_iter().zip(expected) {
+            let found_action =
+                current_layout.get_action(input, modifiers, TermMode::empty());
+            assert_eq!(found_action, expected);
+        }
+    }
+
+    #[test]
+    fn get_action_with_latin_fallback() {
+        let current_layout = BindingsLayout::default();
+        let inputs = vec![
+            InputKind::Char(String::from("с")),
+            InputKind::Physical(Code::KeyC),
+            InputKind::Char(String::from("c")),
+        ];
+        let found_action = current_layout.get_action_with_fallbacks(
+            &inputs,
+            Modifiers::CTRL,
+            TermMode::empty(),
+        );
+        assert_eq!(found_action, BindingAction::Char('\x03'));
+    }
+
+    #[test]
+    fn get_action_with_physical_binding() {
+        let mut current_layout = BindingsLayout::default();
+        let custom_bindings = generate_bindings!(
+            KeyboardBinding;
+            Code::KeyC, Modifiers::CTRL; BindingAction::Copy;
+        );
+        current_layout.add_bindings(custom_bindings);
+        let inputs = vec![
+            InputKind::Char(String::from("с")),
+            InputKind::Physical(Code::KeyC),
+        ];
+        let found_action = current_layout.get_action_with_fallbacks(
+            &inputs,
+            Modifiers::CTRL,
+            TermMode::empty(),
+        );
+        assert_eq!(found_action, BindingAction::Copy);
+        // The logical character still has precedence
+        let found_action = current_layout.get_action_with_fallbacks(
+            &[
+                InputKind::Char(String::from("c")),
+                InputKind::Physical(Code::KeyC),
+            ],
+            Modifiers::CTRL,
+            TermMode::empty(),
+        );
+        assert_eq!(found_action, BindingAction::Char('\x03'));
+    }
+
+    #[test]
+    fn parse_binding_from_text() {
+        let texts = vec![
+            "ctrl+shift+c",
+            "alt+Left",
+            "shift+pageup +ALT_SCREEN ~APP_CURSOR",
+            "ctrl++",
+            "ctrl+physical:KeyC",
+            "cmd+mouse:Left",
+        ];
+        let mut expected: Vec<(KeyboardBinding, BindingAction)> = generate_bindings!(
+            KeyboardBinding;
+            "c",        Modifiers::CTRL | Modifiers::SHIFT; BindingAction::Ignore;
+            ArrowLeft,  Modifiers::ALT;                     BindingAction::Ignore;
+            PageUp,     Modifiers::SHIFT, +TermMode::ALT_SCREEN, ~TermMode::APP_CURSOR; BindingAction::Ignore;
+            "+",        Modifiers::CTRL;                    BindingAction::Ignore;
+            Code::KeyC, Modifiers::CTRL;                    BindingAction::Ignore;
+        );
+        expected.extend(generate_bindings!(
+            MouseBinding;
+            Left, Modifiers::COMMAND; BindingAction::Ignore;
+        ));
+
+        for (text, (expected_binding, _)) in texts.into_iter().zip(expected) {
+            let binding: Binding<InputKind> = text.parse().unwrap();
+            assert_eq!(binding, expected_binding);
+        }
+    }
+
+    #[test]
+    fn parse_binding_errors() {
+        let cases = vec![
+            ("", "the binding is empty"),
+            ("hyper+c", "unknown modifier \"hyper\""),
+            ("ctrl+Clear", "unknown key \"Clear\""),
+            ("ctrl+physical:Fn", "unknown physical key \"Fn\""),
+            ("ctrl+c +UNKNOWN", "unknown terminal mode \"UNKNOWN\""),
+            (
+                "ctrl+c ALT_SCREEN",
+                "the mode \"ALT_SCREEN\" must start with + or ~",
+            ),
+        ];
+
+        for (text, reason) in cases {
+            let err = text.parse::<Binding<InputKind>>().unwrap_err();
+            assert_eq!(err.entry, text);
+            assert_eq!(err.reason, reason);
+        }
+    }
+
+    #[test]
+    fn default_bindings_text_round_trip() {
+        let current_layout = BindingsLayout::default();
+        for (bind, _) in &current_layout.layout {
+            let parsed: Binding<InputKind> = bind.to_string().parse().unwrap();
+            assert_eq!(&parsed, bind);
+        }
+    }
+
+    #[test]
+    fn bindings_config_from_toml() {
+        let config: BindingsConfig = toml::from_str(
+            r#"
+            "ctrl+shift+c" = "Copy"
+            "ctrl+=" = "increase_font_size"
+            "alt+Left" = { esc = "\u001bb" }
+            "shift+PageUp +ALT_SCREEN" = { esc = "\u001b[5;2~" }
+            "ctrl+h" = { char = "\u007f" }
+            "ctrl+shift+t" = { custom = "new_tab" }
+            "#,
+        )
+        .unwrap();
+        let expected = generate_bindings!(
+            KeyboardBinding;
+            "c",      Modifiers::CTRL | Modifiers::SHIFT;       BindingAction::Copy;
+            "=",      Modifiers::CTRL;                          BindingAction::IncreaseFontSize;
+            ArrowLeft, Modifiers::ALT;                          BindingAction::Esc("\x1bb".into());
+            PageUp,   Modifiers::SHIFT, +TermMode::ALT_SCREEN;  BindingAction::Esc("\x1b[5;2~".into());
+            "h",      Modifiers::CTRL;                          BindingAction::Char('\x7f');
+            "t",      Modifiers::CTRL | Modifiers::SHIFT;       BindingAction::Custom("new_tab".into());
+        );
+        assert_eq!(config.parse().unwrap(), expected);
+
+        let serialized =
+            toml::to_string(&expected.into_iter().collect::<BindingsConfig>())
+                .unwrap();
+        assert_eq!(
+            toml::from_str::<BindingsConfig>(&serialized).unwrap(),
+            config
+        );
+    }
+
+    #[test]
+    fn bindings_config_with_bad_entries() {
+        let config: BindingsConfig = toml::from_str(
+            r#"
+            "ctrl+shift+c" = "Copy"
+            "ctrl+shfit+v" = "Paste"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.parse().unwrap_err().to_string(),
+            "invalid binding \"ctrl+shfit+v\": unknown modifier \"shfit\""
+        );
+
+        let err = toml::from_str::<BindingsConfig>(
+            r#"
+            "ctrl+shift+c" = "Cut"
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("ctrl+shift+c"));
+    }
 }
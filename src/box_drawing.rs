@@ -0,0 +1,637 @@
+use crate::decoration::line_thickness;
+use iced::widget::canvas::path::{arc::Elliptical, Builder};
+use iced::widget::canvas::{Frame, Path, Stroke};
+use iced::{Color, Point, Radians, Size, Vector};
+use std::f32::consts::{FRAC_PI_2, PI};
+
+const NONE: u8 = 0;
+const LIGHT: u8 = 1;
+const HEAVY: u8 = 2;
+const DOUBLE: u8 = 3;
+
+/// The up, right, down and left line weights of U+2500..=U+257F,
+/// one nibble per direction, zero for the glyphs that are not lines
+#[rustfmt::skip]
+const LINES: [u16; 128] = [
+    0x0101, 0x0202, 0x1010, 0x2020, 0, 0, 0, 0,
+    0, 0, 0, 0, 0x0110, 0x0210, 0x0120, 0x0220,
+    0x0011, 0x0012, 0x0021, 0x0022, 0x1100, 0x1200, 0x2100, 0x2200,
+    0x1001, 0x1002, 0x2001, 0x2002, 0x1110, 0x1210, 0x2110, 0x1120,
+    0x2120, 0x2210, 0x1220, 0x2220, 0x1011, 0x1012, 0x2011, 0x1021,
+    0x2021, 0x2012, 0x1022, 0x2022, 0x0111, 0x0112, 0x0211, 0x0212,
+    0x0121, 0x0122, 0x0221, 0x0222, 0x1101, 0x1102, 0x1201, 0x1202,
+    0x2101, 0x2102, 0x2201, 0x2202, 0x1111, 0x1112, 0x1211, 0x1212,
+    0x2111, 0x1121, 0x2121, 0x2112, 0x2211, 0x1122, 0x1221, 0x2212,
+    0x1222, 0x2122, 0x2221, 0x2222, 0, 0, 0, 0,
+    0x0303, 0x3030, 0x0310, 0x0130, 0x0330, 0x0013, 0x0031, 0x0033,
+    0x1300, 0x3100, 0x3300, 0x1003, 0x3001, 0x3003, 0x1310, 0x3130,
+    0x3330, 0x1013, 0x3031, 0x3033, 0x0313, 0x0131, 0x0333, 0x1303,
+    0x3101, 0x3303, 0x1313, 0x3131, 0x3333, 0, 0, 0,
+    0, 0, 0, 0, 0x0001, 0x1000, 0x0100, 0x0010,
+    0x0002, 0x2000, 0x0200, 0x0020, 0x0201, 0x1020, 0x0102, 0x2010,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Glyph {
+    /// The up, right, down and left line weights
+    Lines([u8; 4]),
+    Dashes {
+        is_horizontal: bool,
+        weight: u8,
+        count: u8,
+    },
+    /// The arc corner from the bottom/top center
+    /// to the right/left middle of the cell
+    Arc {
+        is_down: bool,
+        is_right: bool,
+    },
+    Diagonals {
+        rising: bool,
+        falling: bool,
+    },
+    /// The left, top, right and bottom edges as the cell fractions
+    Block([f32; 4]),
+    Shade(f32),
+    /// The upper left, upper right, lower left and lower right quadrants
+    Quadrants([bool; 4]),
+    Powerline(char),
+}
+
+fn glyph(c: char) -> Option<Glyph> {
+    let glyph = match c {
+        '\u{2504}'..='\u{250b}' | '\u{254c}'..='\u{254f}' => {
+            let (offset, count) = match c {
+                '\u{2504}'..='\u{2507}' => (c as u32 - 0x2504, 3),
+                '\u{2508}'..='\u{250b}' => (c as u32 - 0x2508, 4),
+                _ => (c as u32 - 0x254c, 2),
+            };
+            Glyph::Dashes {
+                is_horizontal: offset < 2,
+                weight: if offset % 2 == 0 { LIGHT } else { HEAVY },
+                count,
+            }
+        },
+        '\u{256d}' => Glyph::Arc {
+            is_down: true,
+            is_right: true,
+        },
+        '\u{256e}' => Glyph::Arc {
+            is_down: true,
+            is_right: false,
+        },
+        '\u{256f}' => Glyph::Arc {
+            is_down: false,
+            is_right: false,
+        },
+        '\u{2570}' => Glyph::Arc {
+            is_down: false,
+            is_right: true,
+        },
+        '\u{2571}' => Glyph::Diagonals {
+            rising: true,
+            falling: false,
+        },
+        '\u{2572}' => Glyph::Diagonals {
+            rising: false,
+            falling: true,
+        },
+        '\u{2573}' => Glyph::Diagonals {
+            rising: true,
+            falling: true,
+        },
+        '\u{2500}'..='\u{257f}' => {
+            let lines = LINES[(c as u32 - 0x2500) as usize];
+            let weight = |shift: u16| ((lines >> shift) & 0xf) as u8;
+            Glyph::Lines([weight(12), weight(8), weight(4), weight(0)])
+        },
+        '\u{2580}' => Glyph::Block([0.0, 0.0, 1.0, 0.5]),
+        // Lower one eighth..full block
+        '\u{2581}'..='\u{2588}' => {
+            let eighths = (c as u32 - 0x2580) as f32;
+            Glyph::Block([0.0, 1.0 - eighths / 8.0, 1.0, 1.0])
+        },
+        // Left seven eighths..one eighth block
+        '\u{2589}'..='\u{258f}' => {
+            let eighths = (0x2590 - c as u32) as f32;
+            Glyph::Block([0.0, 0.0, eighths / 8.0, 1.0])
+        },
+        '\u{2590}' => Glyph::Block([0.5, 0.0, 1.0, 1.0]),
+        '\u{2591}' => Glyph::Shade(0.25),
+        '\u{2592}' => Glyph::Shade(0.5),
+        '\u{2593}' => Glyph::Shade(0.75),
+        '\u{2594}' => Glyph::Block([0.0, 0.0, 1.0, 0.125]),
+        '\u{2595}' => Glyph::Block([0.875, 0.0, 1.0, 1.0]),
+        '\u{2596}'..='\u{259f}' => {
+            let quadrants = match c {
+                '\u{2596}' => [false, false, true, false],
+                '\u{2597}' => [false, false, false, true],
+                '\u{2598}' => [true, false, false, false],
+                '\u{2599}' => [true, false, true, true],
+                '\u{259a}' => [true, false, false, true],
+                '\u{259b}' => [true, true, true, false],
+                '\u{259c}' => [true, true, false, true],
+                '\u{259d}' => [false, true, false, false],
+                '\u{259e}' => [false, true, true, false],
+                _ => [false, true, true, true],
+            };
+            Glyph::Quadrants(quadrants)
+        },
+        '\u{e0b0}'..='\u{e0bf}' => Glyph::Powerline(c),
+        _ => return None,
+    };
+
+    Some(glyph)
+}
+
+/// Box-drawing U+2500..=U+257F, block elements U+2580..=U+259F
+/// and Powerline separators U+E0B0..=U+E0BF
+pub(crate) fn is_builtin_glyph(c: char) -> bool {
+    glyph(c).is_some()
+}
+
+/// The glyph that is drawn with paths filling the exact cell rectangle
+/// instead of the font, so the glyphs of the neighbor cells are joined
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BuiltinGlyph {
+    pub(crate) c: char,
+    pub(crate) position: Point,
+    pub(crate) cell_size: Size,
+    pub(crate) color: Color,
+}
+
+impl BuiltinGlyph {
+    pub(crate) fn draw(&self, frame: &mut Frame) {
+        let Some(glyph) = glyph(self.c) else {
+            return;
+        };
+
+        match glyph {
+            Glyph::Lines(weights) => self.draw_lines(frame, weights),
+            Glyph::Dashes {
+                is_horizontal,
+                weight,
+                count,
+            } => self.draw_dashes(frame, is_horizontal, weight, count),
+            Glyph::Arc { is_down, is_right } => {
+                self.draw_arc(frame, is_down, is_right)
+            },
+            Glyph::Diagonals { rising, falling } => {
+                self.draw_diagonals(frame, rising, falling)
+            },
+            Glyph::Block([left, top, right, bottom]) => {
+                self.fill_fraction(frame, left, top, right, bottom, self.color)
+            },
+            Glyph::Shade(alpha) => {
+                let color = Color {
+                    a: self.color.a * alpha,
+                    ..self.color
+                };
+                self.fill_fraction(frame, 0.0, 0.0, 1.0, 1.0, color);
+            },
+            Glyph::Quadrants(
+                [upper_left, upper_right, lower_left, lower_right],
+            ) => {
+                let quadrants = [
+                    (upper_left, 0.0, 0.0),
+                    (upper_right, 0.5, 0.0),
+                    (lower_left, 0.0, 0.5),
+                    (lower_right, 0.5, 0.5),
+                ];
+                for (is_filled, left, top) in quadrants {
+                    if is_filled {
+                        self.fill_fraction(
+                            frame,
+                            left,
+                            top,
+                            left + 0.5,
+                            top + 0.5,
+                            self.color,
+                        );
+                    }
+                }
+            },
+            Glyph::Powerline(c) => self.draw_powerline(frame, c),
+        }
+    }
+
+    fn light(&self) -> f32 {
+        line_thickness(self.cell_size.height)
+    }
+
+    fn center(&self) -> Point {
+        Point::new(
+            self.position.x + self.cell_size.width * 0.5,
+            self.position.y + self.cell_size.height * 0.5,
+        )
+    }
+
+    /// Draws the lines from the center to the cell edges, the lines
+    /// are extended through the center to join the perpendicular ones
+    fn draw_lines(&self, frame: &mut Frame, weights: [u8; 4]) {
+        let light = self.light();
+        // The distance between the center and each of the double lines
+        let gap = light;
+        let width = |weight: u8| match weight {
+            LIGHT => light,
+            HEAVY => light * 2.0,
+            DOUBLE => light + gap * 2.0,
+            _ => 0.0,
+        };
+
+        for direction in 0..4 {
+            let weight = weights[direction];
+            let opposite = weights[(direction + 2) % 4];
+            // The perpendicular lines on the negative and positive side,
+            // the left and right ones for the vertical lines
+            // and the up and down ones for the horizontal lines
+            let (negative, positive) = match direction {
+                0 | 2 => (weights[3], weights[1]),
+                _ => (weights[0], weights[2]),
+            };
+
+            match weight {
+                LIGHT | HEAVY => {
+                    let thickness = width(weight);
+                    let start = if negative == DOUBLE && positive == DOUBLE {
+                        if opposite != NONE {
+                            -thickness * 0.5
+                        } else {
+                            gap - light * 0.5
+                        }
+                    } else if negative == DOUBLE || positive == DOUBLE {
+                        -gap - light * 0.5
+                    } else if negative != NONE || positive != NONE {
+                        -(width(negative).max(width(positive)) * 0.5)
+                    } else if opposite != NONE {
+                        -thickness * 0.5
+                    } else {
+                        0.0
+                    };
+                    self.fill_line(frame, direction, start, 0.0, thickness);
+                },
+                DOUBLE => {
+                    for (side, other, offset) in
+                        [(negative, positive, -gap), (positive, negative, gap)]
+                    {
+                        let start = match (side, other) {
+                            (DOUBLE, _) => gap,
+                            (LIGHT | HEAVY, _) => 0.0,
+                            (_, DOUBLE) => -gap,
+                            (_, NONE) => 0.0,
+                            (_, other) => -width(other) * 0.5,
+                        };
+                        self.fill_line(
+                            frame,
+                            direction,
+                            start - light * 0.5,
+                            offset,
+                            light,
+                        );
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// Fills the line going from the center in the up, right, down
+    /// or left direction, `start` is the distance from the center where
+    /// the line begins and `offset` is its shift from the center line
+    fn fill_line(
+        &self,
+        frame: &mut Frame,
+        direction: usize,
+        start: f32,
+        offset: f32,
+        thickness: f32,
+    ) {
+        let Point { x, y } = self.position;
+        let Size { width, height } = self.cell_size;
+        let center = self.center();
+
+        let (top_left, size) = match direction {
+            0 => (
+                Point::new(center.x + offset - thickness * 0.5, y),
+                Size::new(thickness, center.y - start - y),
+            ),
+            1 => (
+                Point::new(
+                    center.x + start,
+                    center.y + offset - thickness * 0.5,
+                ),
+                Size::new(x + width - center.x - start, thickness),
+            ),
+            2 => (
+                Point::new(
+                    center.x + offset - thickness * 0.5,
+                    center.y + start,
+                ),
+                Size::new(thickness, y + height - center.y - start),
+            ),
+            _ => (
+                Point::new(x, center.y + offset - thickness * 0.5),
+                Size::new(center.x - start - x, thickness),
+            ),
+        };
+        frame.fill_rectangle(top_left, size, self.color);
+    }
+
+    fn draw_dashes(
+        &self,
+        frame: &mut Frame,
+        is_horizontal: bool,
+        weight: u8,
+        count: u8,
+    ) {
+        let thickness = self.light() * f32::from(weight);
+        let center = self.center();
+        let length = if is_horizontal {
+            self.cell_size.width
+        } else {
+            self.cell_size.height
+        };
+        let step = length / f32::from(count);
+
+        for i in 0..count {
+            // Every dash is in the middle of its step,
+            // so the dashes of the neighbor cells are evenly spaced
+            let start = step * (f32::from(i) + 0.25);
+            let (top_left, size) = if is_horizontal {
+                (
+                    Point::new(
+                        self.position.x + start,
+                        center.y - thickness * 0.5,
+                    ),
+                    Size::new(step * 0.5, thickness),
+                )
+            } else {
+                (
+                    Point::new(
+                        center.x - thickness * 0.5,
+                        self.position.y + start,
+                    ),
+                    Size::new(thickness, step * 0.5),
+                )
+            };
+            frame.fill_rectangle(top_left, size, self.color);
+        }
+    }
+
+    fn draw_arc(&self, frame: &mut Frame, is_down: bool, is_right: bool) {
+        let Point { x, y } = self.position;
+        let Size { width, height } = self.cell_size;
+        let center = self.center();
+        let radius = width.min(height) * 0.5;
+        let vertical_edge = if is_down { y + height } else { y };
+        let horizontal_edge = if is_right { x + width } else { x };
+        let vertical_end = if is_down {
+            center.y + radius
+        } else {
+            center.y - radius
+        };
+        let horizontal_end = if is_right {
+            center.x + radius
+        } else {
+            center.x - radius
+        };
+
+        let arc = Path::new(|builder| {
+            builder.move_to(Point::new(center.x, vertical_edge));
+            builder.line_to(Point::new(center.x, vertical_end));
+            builder.quadratic_curve_to(
+                center,
+                Point::new(horizontal_end, center.y),
+            );
+            builder.line_to(Point::new(horizontal_edge, center.y));
+        });
+        self.stroke(frame, &arc);
+    }
+
+    fn draw_diagonals(&self, frame: &mut Frame, rising: bool, falling: bool) {
+        let Point { x, y } = self.position;
+        let Size { width, height } = self.cell_size;
+
+        if rising {
+            let line =
+                Path::line(Point::new(x + width, y), Point::new(x, y + height));
+            self.stroke(frame, &line);
+        }
+        if falling {
+            let line =
+                Path::line(Point::new(x, y), Point::new(x + width, y + height));
+            self.stroke(frame, &line);
+        }
+    }
+
+    fn draw_powerline(&self, frame: &mut Frame, c: char) {
+        let Point { x, y } = self.position;
+        let Size { width, height } = self.cell_size;
+        let left = x;
+        let right = x + width;
+        let top = y;
+        let bottom = y + height;
+        let middle = y + height * 0.5;
+
+        let polygon = |points: &[Point]| {
+            Path::new(|builder| {
+                builder.move_to(points[0]);
+                for point in &points[1..] {
+                    builder.line_to(*point);
+                }
+            })
+        };
+        let half_ellipse = |builder: &mut Builder, is_right: bool| {
+            let (center_x, start_angle) = if is_right {
+                (left, -FRAC_PI_2)
+            } else {
+                (right, FRAC_PI_2)
+            };
+            builder.ellipse(Elliptical {
+                center: Point::new(center_x, middle),
+                radii: Vector::new(width, height * 0.5),
+                rotation: Radians(0.0),
+                start_angle: Radians(start_angle),
+                end_angle: Radians(start_angle + PI),
+            });
+        };
+
+        let top_left = Point::new(left, top);
+        let top_right = Point::new(right, top);
+        let bottom_left = Point::new(left, bottom);
+        let bottom_right = Point::new(right, bottom);
+        match c {
+            '\u{e0b0}' => frame.fill(
+                &polygon(&[top_left, Point::new(right, middle), bottom_left]),
+                self.color,
+            ),
+            '\u{e0b1}' => self.stroke(
+                frame,
+                &polygon(&[top_left, Point::new(right, middle), bottom_left]),
+            ),
+            '\u{e0b2}' => frame.fill(
+                &polygon(&[top_right, Point::new(left, middle), bottom_right]),
+                self.color,
+            ),
+            '\u{e0b3}' => self.stroke(
+                frame,
+                &polygon(&[top_right, Point::new(left, middle), bottom_right]),
+            ),
+            '\u{e0b4}' => frame.fill(
+                &Path::new(|builder| half_ellipse(builder, true)),
+                self.color,
+            ),
+            '\u{e0b5}' => self.stroke(
+                frame,
+                &Path::new(|builder| half_ellipse(builder, true)),
+            ),
+            '\u{e0b6}' => frame.fill(
+                &Path::new(|builder| half_ellipse(builder, false)),
+                self.color,
+            ),
+            '\u{e0b7}' => self.stroke(
+                frame,
+                &Path::new(|builder| half_ellipse(builder, false)),
+            ),
+            '\u{e0b8}' => frame.fill(
+                &polygon(&[top_left, bottom_left, bottom_right]),
+                self.color,
+            ),
+            '\u{e0b9}' | '\u{e0bf}' => {
+                self.stroke(frame, &Path::line(top_left, bottom_right))
+            },
+            '\u{e0ba}' => frame.fill(
+                &polygon(&[top_right, bottom_right, bottom_left]),
+                self.color,
+            ),
+            '\u{e0bb}' | '\u{e0bd}' => {
+                self.stroke(frame, &Path::line(top_right, bottom_left))
+            },
+            '\u{e0bc}' => frame.fill(
+                &polygon(&[top_left, top_right, bottom_left]),
+                self.color,
+            ),
+            '\u{e0be}' => frame.fill(
+                &polygon(&[top_left, top_right, bottom_right]),
+                self.color,
+            ),
+            _ => {},
+        }
+    }
+
+    fn fill_fraction(
+        &self,
+        frame: &mut Frame,
+        left: f32,
+        top: f32,
+        right: f32,
+        bottom: f32,
+        color: Color,
+    ) {
+        let Size { width, height } = self.cell_size;
+        frame.fill_rectangle(
+            Point::new(
+                self.position.x + width * left,
+                self.position.y + height * top,
+            ),
+            Size::new(width * (right - left), height * (bottom - top)),
+            color,
+        );
+    }
+
+    fn stroke(&self, frame: &mut Frame, path: &Path) {
+        frame.stroke(
+            path,
+            Stroke::default()
+                .with_width(self.light())
+                .with_color(self.color),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glyph, is_builtin_glyph, Glyph};
+
+    #[test]
+    fn builtin_glyph_ranges() {
+        let ranges = [
+            '\u{2500}'..='\u{257f}',
+            '\u{2580}'..='\u{259f}',
+            '\u{e0b0}'..='\u{e0bf}',
+        ];
+        for c in ranges.into_iter().flatten() {
+            let glyph = glyph(c);
+            assert!(glyph.is_some(), "{:?}", c);
+            assert_ne!(glyph, Some(Glyph::Lines([0; 4])), "{:?}", c);
+        }
+
+        assert!(!is_builtin_glyph('a'));
+        assert!(!is_builtin_glyph('\u{24ff}'));
+        assert!(!is_builtin_glyph('\u{25a0}'));
+        assert!(!is_builtin_glyph('\u{e0c0}'));
+    }
+
+    #[test]
+    fn box_drawing_lines() {
+        let cases = [
+            ('─', [0, 1, 0, 1]),
+            ('┃', [2, 0, 2, 0]),
+            ('┌', [0, 1, 1, 0]),
+            ('┥', [1, 0, 1, 2]),
+            ('┼', [1, 1, 1, 1]),
+            ('╋', [2, 2, 2, 2]),
+            ('╄', [2, 2, 1, 1]),
+            ('═', [0, 3, 0, 3]),
+            ('╔', [0, 3, 3, 0]),
+            ('╡', [1, 0, 1, 3]),
+            ('╫', [3, 1, 3, 1]),
+            ('╬', [3, 3, 3, 3]),
+            ('╴', [0, 0, 0, 1]),
+            ('╿', [2, 0, 1, 0]),
+        ];
+        for (c, weights) in cases {
+            assert_eq!(glyph(c), Some(Glyph::Lines(weights)), "{:?}", c);
+        }
+
+        assert_eq!(
+            glyph('┉'),
+            Some(Glyph::Dashes {
+                is_horizontal: true,
+                weight: 2,
+                count: 4
+            })
+        );
+        assert_eq!(
+            glyph('╎'),
+            Some(Glyph::Dashes {
+                is_horizontal: false,
+                weight: 1,
+                count: 2
+            })
+        );
+    }
+
+    #[test]
+    fn block_elements() {
+        let cases = [
+            ('▀', [0.0, 0.0, 1.0, 0.5]),
+            ('▁', [0.0, 0.875, 1.0, 1.0]),
+            ('▄', [0.0, 0.5, 1.0, 1.0]),
+            ('█', [0.0, 0.0, 1.0, 1.0]),
+            ('▉', [0.0, 0.0, 0.875, 1.0]),
+            ('▌', [0.0, 0.0, 0.5, 1.0]),
+            ('▏', [0.0, 0.0, 0.125, 1.0]),
+            ('▐', [0.5, 0.0, 1.0, 1.0]),
+        ];
+        for (c, block) in cases {
+            assert_eq!(glyph(c), Some(Glyph::Block(block)), "{:?}", c);
+        }
+
+        assert_eq!(glyph('▒'), Some(Glyph::Shade(0.5)));
+        assert_eq!(
+            glyph('▚'),
+            Some(Glyph::Quadrants([true, false, false, true]))
+        );
+    }
+}
@@ -0,0 +1,876 @@
+use crate::theme::{self, ColorCube, ColorPalette, DIM_FACTOR};
+use alacritty_terminal::vte::ansi::Rgb;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+
+const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorSchemeError {
+    pub format: &'static str,
+    pub reason: String,
+}
+
+impl fmt::Display for ColorSchemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {} color scheme: {}", self.format, self.reason)
+    }
+}
+
+impl std::error::Error for ColorSchemeError {}
+
+impl ColorPalette {
+    /// Builds the palette from the `[colors]` tables of an Alacritty
+    /// config or theme. Missing bright colors fall back to the normal ones.
+    pub fn from_alacritty_toml(source: &str) -> Result<Self, ColorSchemeError> {
+        const FORMAT: &str = "Alacritty";
+
+        #[derive(Deserialize)]
+        struct Config {
+            colors: Colors,
+        }
+
+        #[derive(Deserialize)]
+        struct Colors {
+            primary: Primary,
+            normal: Ansi,
+            bright: Option<Ansi>,
+            dim: Option<Ansi>,
+            cursor: Option<CursorColors>,
+            selection: Option<SelectionColors>,
+            #[serde(default)]
+            indexed_colors: Vec<IndexedColor>,
+        }
+
+        #[derive(Deserialize)]
+        struct CursorColors {
+            text: Option<String>,
+            cursor: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct SelectionColors {
+            text: Option<String>,
+            background: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct IndexedColor {
+            index: u8,
+            color: String,
+        }
+
+        #[derive(Deserialize)]
+        struct Primary {
+            foreground: Option<String>,
+            background: Option<String>,
+            bright_foreground: Option<String>,
+            dim_foreground: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct Ansi {
+            black: Option<String>,
+            red: Option<String>,
+            green: Option<String>,
+            yellow: Option<String>,
+            blue: Option<String>,
+            magenta: Option<String>,
+            cyan: Option<String>,
+            white: Option<String>,
+        }
+
+        impl Ansi {
+            fn named_colors(
+                &self,
+                table: &str,
+            ) -> [(String, Option<&String>); 8] {
+                let colors = self.colors();
+                std::array::from_fn(|i| {
+                    (format!("{}.{}", table, ANSI_NAMES[i]), colors[i])
+                })
+            }
+
+            fn colors(&self) -> [Option<&String>; 8] {
+                [
+                    self.black.as_ref(),
+                    self.red.as_ref(),
+                    self.green.as_ref(),
+                    self.yellow.as_ref(),
+                    self.blue.as_ref(),
+                    self.magenta.as_ref(),
+                    self.cyan.as_ref(),
+                    self.white.as_ref(),
+                ]
+            }
+        }
+
+        let config: Config = toml::from_str(source)
+            .map_err(|e| scheme_error(FORMAT, e.message()))?;
+        let colors = config.colors;
+        let primary = &colors.primary;
+
+        let normal =
+            ansi_colors(FORMAT, colors.normal.named_colors("colors.normal"))?;
+        let bright = match &colors.bright {
+            Some(bright) => {
+                ansi_colors(FORMAT, bright.named_colors("colors.bright"))?
+            },
+            None => normal,
+        };
+        let mut dim = [None; 8];
+        if let Some(dim_colors) = &colors.dim {
+            let named_colors = dim_colors.named_colors("colors.dim");
+            for (i, (name, value)) in named_colors.into_iter().enumerate() {
+                dim[i] = optional_color(FORMAT, &name, value)?;
+            }
+        }
+
+        // `CellForeground` and `CellBackground` are the default behavior
+        let cell_color = |name: &str, value: Option<&String>| {
+            let value = value.filter(|value| {
+                value.as_str() != "CellForeground"
+                    && value.as_str() != "CellBackground"
+            });
+            optional_color(FORMAT, name, value)
+        };
+        let cursor = colors.cursor.as_ref();
+        let selection = colors.selection.as_ref();
+
+        let indexed = colors
+            .indexed_colors
+            .iter()
+            .map(|indexed| {
+                let name = format!("colors.indexed_colors[{}]", indexed.index);
+                let color =
+                    required_color(FORMAT, &name, Some(&indexed.color))?;
+                Ok((indexed.index, color))
+            })
+            .collect::<Result<Vec<(u8, Rgb)>, ColorSchemeError>>()?;
+
+        Ok(SchemeColors {
+            foreground: required_color(
+                FORMAT,
+                "colors.primary.foreground",
+                primary.foreground.as_ref(),
+            )?,
+            background: required_color(
+                FORMAT,
+                "colors.primary.background",
+                primary.background.as_ref(),
+            )?,
+            bright_foreground: optional_color(
+                FORMAT,
+                "colors.primary.bright_foreground",
+                primary.bright_foreground.as_ref(),
+            )?,
+            dim_foreground: optional_color(
+                FORMAT,
+                "colors.primary.dim_foreground",
+                primary.dim_foreground.as_ref(),
+            )?,
+            cursor: cell_color(
+                "colors.cursor.cursor",
+                cursor.and_then(|c| c.cursor.as_ref()),
+            )?,
+            cursor_text: cell_color(
+                "colors.cursor.text",
+                cursor.and_then(|c| c.text.as_ref()),
+            )?,
+            selection_background: cell_color(
+                "colors.selection.background",
+                selection.and_then(|c| c.background.as_ref()),
+            )?,
+            selection_foreground: cell_color(
+                "colors.selection.text",
+                selection.and_then(|c| c.text.as_ref()),
+            )?,
+            normal,
+            bright,
+            dim,
+            indexed,
+        }
+        .into())
+    }
+
+    /// Builds the palette from a base16 scheme, both the classic flat
+    /// `base00`..`base0F` keys and the newer `palette` table are supported.
+    pub fn from_base16_yaml(source: &str) -> Result<Self, ColorSchemeError> {
+        const FORMAT: &str = "base16";
+
+        let scheme: serde_yaml_ng::Value = serde_yaml_ng::from_str(source)
+            .map_err(|e| scheme_error(FORMAT, e))?;
+        let colors = scheme.get("palette").unwrap_or(&scheme);
+        let mut base = [Rgb::default(); 16];
+        for (i, color) in base.iter_mut().enumerate() {
+            let name = format!("base{:02X}", i);
+            let value = colors
+                .get(name.as_str())
+                .or_else(|| colors.get(name.to_lowercase().as_str()))
+                .map(|value| {
+                    value.as_str().ok_or_else(|| {
+                        scheme_error(
+                            FORMAT,
+                            format!("color \"{}\" is not a string", name),
+                        )
+                    })
+                })
+                .transpose()?;
+            *color = required_color(FORMAT, &name, value)?;
+        }
+
+        // The mapping of base16-shell
+        let normal = [
+            base[0x00], base[0x08], base[0x0B], base[0x0A], base[0x0D],
+            base[0x0E], base[0x0C], base[0x05],
+        ];
+        let mut bright = normal;
+        bright[0] = base[0x03];
+        bright[7] = base[0x07];
+        let indexed = vec![
+            (16, base[0x09]),
+            (17, base[0x0F]),
+            (18, base[0x01]),
+            (19, base[0x02]),
+            (20, base[0x04]),
+            (21, base[0x06]),
+        ];
+
+        Ok(SchemeColors {
+            foreground: base[0x05],
+            background: base[0x00],
+            bright_foreground: None,
+            dim_foreground: None,
+            cursor: None,
+            cursor_text: None,
+            selection_background: None,
+            selection_foreground: None,
+            normal,
+            bright,
+            dim: [None; 8],
+            indexed,
+        }
+        .into())
+    }
+
+    /// Builds the palette from an iTerm2 `.itermcolors` property list,
+    /// the `Bold Color` becomes the bright foreground.
+    pub fn from_iterm2_plist(source: &[u8]) -> Result<Self, ColorSchemeError> {
+        const FORMAT: &str = "iTerm2";
+
+        #[derive(Deserialize)]
+        struct Component {
+            #[serde(rename = "Red Component")]
+            red: f64,
+            #[serde(rename = "Green Component")]
+            green: f64,
+            #[serde(rename = "Blue Component")]
+            blue: f64,
+        }
+
+        impl From<&Component> for Rgb {
+            fn from(component: &Component) -> Self {
+                let channel =
+                    |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+                Rgb {
+                    r: channel(component.red),
+                    g: channel(component.green),
+                    b: channel(component.blue),
+                }
+            }
+        }
+
+        let scheme: HashMap<String, plist::Value> =
+            plist::from_bytes(source).map_err(|e| scheme_error(FORMAT, e))?;
+        let color = |name: &str| -> Result<Option<Rgb>, ColorSchemeError> {
+            scheme
+                .get(name)
+                .map(|value| {
+                    plist::from_value::<Component>(value)
+                        .map(|component| Rgb::from(&component))
+                        .map_err(|e| {
+                            scheme_error(
+                                FORMAT,
+                                format!("invalid color \"{}\": {}", name, e),
+                            )
+                        })
+                })
+                .transpose()
+        };
+        let required = |name: &str| -> Result<Rgb, ColorSchemeError> {
+            color(name)?.ok_or_else(|| {
+                scheme_error(FORMAT, format!("missing color \"{}\"", name))
+            })
+        };
+
+        let mut ansi = [Rgb::default(); 16];
+        for (i, color) in ansi.iter_mut().enumerate() {
+            *color = required(&format!("Ansi {} Color", i))?;
+        }
+        let mut normal = [Rgb::default(); 8];
+        let mut bright = [Rgb::default(); 8];
+        normal.copy_from_slice(&ansi[..8]);
+        bright.copy_from_slice(&ansi[8..]);
+
+        Ok(SchemeColors {
+            foreground: required("Foreground Color")?,
+            background: required("Background Color")?,
+            bright_foreground: color("Bold Color")?,
+            dim_foreground: None,
+            cursor: color("Cursor Color")?,
+            cursor_text: color("Cursor Text Color")?,
+            selection_background: color("Selection Color")?,
+            selection_foreground: color("Selected Text Color")?,
+            normal,
+            bright,
+            dim: [None; 8],
+            indexed: Vec::new(),
+        }
+        .into())
+    }
+
+    /// Builds the palette from a Windows Terminal scheme object,
+    /// the one that is listed in the `schemes` array of `settings.json`.
+    pub fn from_windows_terminal_json(
+        source: &str,
+    ) -> Result<Self, ColorSchemeError> {
+        const FORMAT: &str = "Windows Terminal";
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Scheme {
+            foreground: String,
+            background: String,
+            black: String,
+            red: String,
+            green: String,
+            yellow: String,
+            blue: String,
+            purple: String,
+            cyan: String,
+            white: String,
+            bright_black: String,
+            bright_red: String,
+            bright_green: String,
+            bright_yellow: String,
+            bright_blue: String,
+            bright_purple: String,
+            bright_cyan: String,
+            bright_white: String,
+            cursor_color: Option<String>,
+            selection_background: Option<String>,
+        }
+
+        let scheme: Scheme = serde_json::from_str(source)
+            .map_err(|e| scheme_error(FORMAT, e))?;
+        let named = |name: &str, value| (name.to_string(), Some(value));
+        let normal = ansi_colors(
+            FORMAT,
+            [
+                named("black", &scheme.black),
+                named("red", &scheme.red),
+                named("green", &scheme.green),
+                named("yellow", &scheme.yellow),
+                named("blue", &scheme.blue),
+                named("purple", &scheme.purple),
+                named("cyan", &scheme.cyan),
+                named("white", &scheme.white),
+            ],
+        )?;
+        let bright = ansi_colors(
+            FORMAT,
+            [
+                named("brightBlack", &scheme.bright_black),
+                named("brightRed", &scheme.bright_red),
+                named("brightGreen", &scheme.bright_green),
+                named("brightYellow", &scheme.bright_yellow),
+                named("brightBlue", &scheme.bright_blue),
+                named("brightPurple", &scheme.bright_purple),
+                named("brightCyan", &scheme.bright_cyan),
+                named("brightWhite", &scheme.bright_white),
+            ],
+        )?;
+
+        Ok(SchemeColors {
+            foreground: required_color(
+                FORMAT,
+                "foreground",
+                Some(&scheme.foreground),
+            )?,
+            background: required_color(
+                FORMAT,
+                "background",
+                Some(&scheme.background),
+            )?,
+            bright_foreground: None,
+            dim_foreground: None,
+            cursor: optional_color(
+                FORMAT,
+                "cursorColor",
+                scheme.cursor_color.as_ref(),
+            )?,
+            cursor_text: None,
+            selection_background: optional_color(
+                FORMAT,
+                "selectionBackground",
+                scheme.selection_background.as_ref(),
+            )?,
+            selection_foreground: None,
+            normal,
+            bright,
+            dim: [None; 8],
+            indexed: Vec::new(),
+        }
+        .into())
+    }
+}
+
+/// The colors every format is reduced to, the missing dim colors
+/// are derived from the normal ones
+struct SchemeColors {
+    foreground: Rgb,
+    background: Rgb,
+    bright_foreground: Option<Rgb>,
+    dim_foreground: Option<Rgb>,
+    cursor: Option<Rgb>,
+    cursor_text: Option<Rgb>,
+    selection_background: Option<Rgb>,
+    selection_foreground: Option<Rgb>,
+    normal: [Rgb; 8],
+    bright: [Rgb; 8],
+    dim: [Option<Rgb>; 8],
+    indexed: Vec<(u8, Rgb)>,
+}
+
+impl From<SchemeColors> for ColorPalette {
+    fn from(colors: SchemeColors) -> Self {
+        let normal = |i: usize| to_hex(colors.normal[i]);
+        let bright = |i: usize| to_hex(colors.bright[i]);
+        let dim = |i: usize| {
+            to_hex(colors.dim[i].unwrap_or_else(|| dim_color(colors.normal[i])))
+        };
+
+        Self {
+            foreground: to_hex(colors.foreground),
+            background: to_hex(colors.background),
+            black: normal(0),
+            red: normal(1),
+            green: normal(2),
+            yellow: normal(3),
+            blue: normal(4),
+            magenta: normal(5),
+            cyan: normal(6),
+            white: normal(7),
+            bright_black: bright(0),
+            bright_red: bright(1),
+            bright_green: bright(2),
+            bright_yellow: bright(3),
+            bright_blue: bright(4),
+            bright_magenta: bright(5),
+            bright_cyan: bright(6),
+            bright_white: bright(7),
+            bright_foreground: colors.bright_foreground.map(to_hex),
+            dim_foreground: to_hex(
+                colors
+                    .dim_foreground
+                    .unwrap_or_else(|| dim_color(colors.foreground)),
+            ),
+            dim_black: dim(0),
+            dim_red: dim(1),
+            dim_green: dim(2),
+            dim_yellow: dim(3),
+            dim_blue: dim(4),
+            dim_magenta: dim(5),
+            dim_cyan: dim(6),
+            dim_white: dim(7),
+            cursor: colors.cursor.map(to_hex),
+            cursor_text: colors.cursor_text.map(to_hex),
+            selection_background: colors.selection_background.map(to_hex),
+            selection_foreground: colors.selection_foreground.map(to_hex),
+            indexed_colors: colors
+                .indexed
+                .iter()
+                .map(|(index, color)| (*index, to_hex(*color)))
+                .collect(),
+            color_cube: ColorCube::default(),
+        }
+    }
+}
+
+fn scheme_error(
+    format: &'static str,
+    reason: impl fmt::Display,
+) -> ColorSchemeError {
+    ColorSchemeError {
+        format,
+        reason: reason.to_string().trim().to_string(),
+    }
+}
+
+/// Parses the black..white colors given with their names
+fn ansi_colors(
+    format: &'static str,
+    values: [(String, Option<&String>); 8],
+) -> Result<[Rgb; 8], ColorSchemeError> {
+    let mut colors = [Rgb::default(); 8];
+    for (i, (name, value)) in values.into_iter().enumerate() {
+        colors[i] = required_color(format, &name, value)?;
+    }
+
+    Ok(colors)
+}
+
+fn required_color<S: AsRef<str>>(
+    format: &'static str,
+    name: &str,
+    value: Option<S>,
+) -> Result<Rgb, ColorSchemeError> {
+    optional_color(format, name, value)?.ok_or_else(|| {
+        scheme_error(format, format!("missing color \"{}\"", name))
+    })
+}
+
+fn optional_color<S: AsRef<str>>(
+    format: &'static str,
+    name: &str,
+    value: Option<S>,
+) -> Result<Option<Rgb>, ColorSchemeError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    parse_color(value.as_ref()).map(Some).ok_or_else(|| {
+        scheme_error(
+            format,
+            format!("invalid color \"{}\": \"{}\"", name, value.as_ref()),
+        )
+    })
+}
+
+/// Parses the palette colors, along with the `0xrrggbb` and bare `rrggbb`
+/// colors of the scheme files
+fn parse_color(value: &str) -> Option<Rgb> {
+    let value = value.trim();
+    let color = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => theme::parse_color(&format!("#{}", hex)),
+        None => theme::parse_color(value)
+            .or_else(|_| theme::parse_color(&format!("#{}", value))),
+    }
+    .ok()?;
+
+    let [r, g, b, _] = color.into_rgba8();
+    Some(Rgb { r, g, b })
+}
+
+fn dim_color(color: Rgb) -> Rgb {
+    let channel = |value: u8| (f32::from(value) * DIM_FACTOR) as u8;
+    Rgb {
+        r: channel(color.r),
+        g: channel(color.g),
+        b: channel(color.b),
+    }
+}
+
+fn to_hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALACRITTY_TOML: &str = r##"
+        [colors.primary]
+        background = "#1d1f21"
+        foreground = "0xc5c8c6"
+
+        [colors.normal]
+        black = "#1d1f21"
+        red = "#cc6666"
+        green = "#b5bd68"
+        yellow = "#f0c674"
+        blue = "#81a2be"
+        magenta = "#b294bb"
+        cyan = "#8abeb7"
+        white = "#c5c8c6"
+
+        [colors.bright]
+        black = "#666666"
+        red = "#d54e53"
+        green = "#b9ca4a"
+        yellow = "#e7c547"
+        blue = "#7aa6da"
+        magenta = "#c397d8"
+        cyan = "#70c0b1"
+        white = "#eaeaea"
+
+        [colors.dim]
+        red = "#AA0000"
+
+        [[colors.indexed_colors]]
+        index = 16
+        color = "#ff9900"
+
+        [colors.cursor]
+        text = "CellBackground"
+        cursor = "#ffffff"
+
+        [colors.selection]
+        background = "#373b41"
+    "##;
+
+    #[test]
+    fn load_alacritty_toml() {
+        let palette =
+            ColorPalette::from_alacritty_toml(ALACRITTY_TOML).unwrap();
+        assert_eq!(palette.background, "#1d1f21");
+        assert_eq!(palette.foreground, "#c5c8c6");
+        assert_eq!(palette.magenta, "#b294bb");
+        assert_eq!(palette.bright_white, "#eaeaea");
+        assert_eq!(palette.bright_foreground, None);
+        assert_eq!(palette.dim_red, "#aa0000");
+        assert_eq!(palette.cursor, Some(String::from("#ffffff")));
+        assert_eq!(palette.cursor_text, None);
+        assert_eq!(palette.selection_background, Some(String::from("#373b41")));
+        assert_eq!(palette.selection_foreground, None);
+        assert_eq!(
+            palette.indexed_colors,
+            HashMap::from([(16, String::from("#ff9900"))])
+        );
+        // Derived from the normal colors
+        assert_eq!(
+            palette.dim_green,
+            to_hex(dim_color(Rgb {
+                r: 0xb5,
+                g: 0xbd,
+                b: 0x68
+            }))
+        );
+        assert_eq!(
+            palette.dim_foreground,
+            to_hex(dim_color(Rgb {
+                r: 0xc5,
+                g: 0xc8,
+                b: 0xc6
+            }))
+        );
+    }
+
+    #[test]
+    fn alacritty_toml_errors() {
+        let missing = ALACRITTY_TOML.replace("red = \"#cc6666\"", "");
+        assert_eq!(
+            ColorPalette::from_alacritty_toml(&missing)
+                .unwrap_err()
+                .to_string(),
+            "invalid Alacritty color scheme: missing color \"colors.normal.red\""
+        );
+
+        let invalid = ALACRITTY_TOML.replace("#b5bd68", "#b5bd6");
+        assert_eq!(
+            ColorPalette::from_alacritty_toml(&invalid)
+                .unwrap_err()
+                .to_string(),
+            "invalid Alacritty color scheme: invalid color \"colors.normal.green\": \"#b5bd6\""
+        );
+
+        assert!(ColorPalette::from_alacritty_toml("[colors").is_err());
+    }
+
+    #[test]
+    fn parse_scheme_colors() {
+        let expected = Some(Rgb {
+            r: 0x1d,
+            g: 0x1f,
+            b: 0x21,
+        });
+        for value in [
+            "#1d1f21",
+            "0x1d1f21",
+            "0X1D1F21",
+            "1d1f21",
+            "rgb(29, 31, 33)",
+        ] {
+            assert_eq!(parse_color(value), expected, "{}", value);
+        }
+        assert_eq!(parse_color("#b5bd6"), None);
+        assert_eq!(parse_color("purple"), None);
+    }
+
+    #[test]
+    fn derive_dim_colors_like_default_palette() {
+        let default_palette = ColorPalette::default();
+        let cases = [
+            (&default_palette.red, &default_palette.dim_red),
+            (&default_palette.green, &default_palette.dim_green),
+            (&default_palette.blue, &default_palette.dim_blue),
+            (&default_palette.cyan, &default_palette.dim_cyan),
+        ];
+        for (normal, dim) in cases {
+            let color = parse_color(normal).unwrap();
+            assert_eq!(&to_hex(dim_color(color)), dim);
+        }
+    }
+
+    #[test]
+    fn load_base16_yaml() {
+        let flat = r#"
+scheme: "Tomorrow Night"
+author: "Chris Kempson"
+base00: "1d1f21"
+base01: "282a2e"
+base02: "373b41"
+base03: "969896"
+base04: "b4b7b4"
+base05: "c5c8c6"
+base06: "e0e0e0"
+base07: "ffffff"
+base08: "cc6666"
+base09: "de935f"
+base0A: "f0c674"
+base0B: "b5bd68"
+base0C: "8abeb7"
+base0D: "81a2be"
+base0E: "b294bb"
+base0F: "a3685a"
+"#;
+        let palette = ColorPalette::from_base16_yaml(flat).unwrap();
+        assert_eq!(palette.background, "#1d1f21");
+        assert_eq!(palette.foreground, "#c5c8c6");
+        assert_eq!(palette.red, "#cc6666");
+        assert_eq!(palette.yellow, "#f0c674");
+        assert_eq!(palette.bright_black, "#969896");
+        assert_eq!(palette.bright_red, "#cc6666");
+        assert_eq!(palette.bright_white, "#ffffff");
+        assert_eq!(palette.indexed_colors[&16], "#de935f");
+        assert_eq!(palette.indexed_colors[&21], "#e0e0e0");
+
+        let mut nested = String::from("system: \"base16\"\npalette:\n");
+        for line in flat.lines().filter(|line| line.starts_with("base")) {
+            nested.push_str(&format!("  {}\n", line.replacen('"', "\"#", 1)));
+        }
+        assert_eq!(
+            ColorPalette::from_base16_yaml(&nested).unwrap().dim_red,
+            palette.dim_red
+        );
+
+        let missing = flat.replace("base0C: \"8abeb7\"", "");
+        assert_eq!(
+            ColorPalette::from_base16_yaml(&missing)
+                .unwrap_err()
+                .to_string(),
+            "invalid base16 color scheme: missing color \"base0C\""
+        );
+    }
+
+    fn iterm2_color(name: &str, (r, g, b): (f64, f64, f64)) -> String {
+        format!(
+            "<key>{}</key>
+            <dict>
+                <key>Color Space</key><string>sRGB</string>
+                <key>Red Component</key><real>{}</real>
+                <key>Green Component</key><real>{}</real>
+                <key>Blue Component</key><real>{}</real>
+            </dict>",
+            name, r, g, b
+        )
+    }
+
+    #[test]
+    fn load_iterm2_plist() {
+        let mut colors = vec![
+            iterm2_color("Background Color", (0.0, 0.0, 0.0)),
+            iterm2_color("Foreground Color", (1.0, 1.0, 1.0)),
+            iterm2_color("Bold Color", (1.0, 0.5, 0.0)),
+        ];
+        for i in 0..16 {
+            let value = f64::from(i) / 15.0;
+            colors.push(iterm2_color(
+                &format!("Ansi {} Color", i),
+                (value, 0.0, 1.0 - value),
+            ));
+        }
+        let plist = |colors: &[String]| {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0"><dict>{}</dict></plist>"#,
+                colors.join("\n")
+            )
+        };
+
+        let palette =
+            ColorPalette::from_iterm2_plist(plist(&colors).as_bytes()).unwrap();
+        assert_eq!(palette.background, "#000000");
+        assert_eq!(palette.foreground, "#ffffff");
+        assert_eq!(palette.bright_foreground, Some(String::from("#ff8000")));
+        assert_eq!(palette.black, "#0000ff");
+        assert_eq!(palette.bright_white, "#ff0000");
+
+        colors.remove(0);
+        assert_eq!(
+            ColorPalette::from_iterm2_plist(plist(&colors).as_bytes())
+                .unwrap_err()
+                .to_string(),
+            "invalid iTerm2 color scheme: missing color \"Background Color\""
+        );
+        assert!(ColorPalette::from_iterm2_plist(b"<plist>").is_err());
+    }
+
+    #[test]
+    fn load_windows_terminal_json() {
+        let scheme = r##"{
+            "name": "Campbell",
+            "foreground": "#CCCCCC",
+            "background": "#0C0C0C",
+            "cursorColor": "#FFFFFF",
+            "selectionBackground": "#FFFFFF",
+            "black": "#0C0C0C",
+            "red": "#C50F1F",
+            "green": "#13A10E",
+            "yellow": "#C19C00",
+            "blue": "#0037DA",
+            "purple": "#881798",
+            "cyan": "#3A96DD",
+            "white": "#CCCCCC",
+            "brightBlack": "#767676",
+            "brightRed": "#E74856",
+            "brightGreen": "#16C60C",
+            "brightYellow": "#F9F1A5",
+            "brightBlue": "#3B78FF",
+            "brightPurple": "#B4009E",
+            "brightCyan": "#61D6D6",
+            "brightWhite": "#F2F2F2"
+        }"##;
+        let palette = ColorPalette::from_windows_terminal_json(scheme).unwrap();
+        assert_eq!(palette.background, "#0c0c0c");
+        assert_eq!(palette.magenta, "#881798");
+        assert_eq!(palette.bright_magenta, "#b4009e");
+        assert_eq!(palette.cursor, Some(String::from("#ffffff")));
+        assert_eq!(palette.selection_background, Some(String::from("#ffffff")));
+        assert_eq!(
+            palette.dim_red,
+            to_hex(dim_color(Rgb {
+                r: 0xc5,
+                g: 0x0f,
+                b: 0x1f
+            }))
+        );
+
+        let invalid = scheme.replace("#B4009E", "purple");
+        assert_eq!(
+            ColorPalette::from_windows_terminal_json(&invalid)
+                .unwrap_err()
+                .to_string(),
+            "invalid Windows Terminal color scheme: invalid color \"brightPurple\": \"purple\""
+        );
+        let missing = scheme.replace("\"brightCyan\": \"#61D6D6\",", "");
+        assert!(ColorPalette::from_windows_terminal_json(&missing).is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use crate::settings::{CursorSettings, CursorShape as DefaultCursorShape};
+use alacritty_terminal::vte::ansi::{CursorShape, CursorStyle};
+use std::time::{Duration, Instant};
+
+impl From<&CursorSettings> for CursorStyle {
+    fn from(settings: &CursorSettings) -> Self {
+        let shape = match settings.shape {
+            DefaultCursorShape::Block => CursorShape::Block,
+            DefaultCursorShape::Underline => CursorShape::Underline,
+            DefaultCursorShape::Beam => CursorShape::Beam,
+        };
+
+        Self {
+            shape,
+            blinking: settings.blinking,
+        }
+    }
+}
+
+/// Blinking phase of the cursor, it is switched by the timer subscription
+/// and stays visible when the terminal has not got input for a while
+#[derive(Debug, Clone)]
+pub(crate) struct CursorBlink {
+    visible: bool,
+    last_input: Instant,
+    pub(crate) interval: Duration,
+    timeout: Duration,
+}
+
+impl CursorBlink {
+    pub(crate) fn new(settings: &CursorSettings, now: Instant) -> Self {
+        Self {
+            visible: true,
+            last_input: now,
+            interval: settings.blink_interval,
+            timeout: settings.blink_timeout,
+        }
+    }
+
+    pub(crate) fn reset(&mut self, now: Instant) {
+        self.visible = true;
+        self.last_input = now;
+    }
+
+    pub(crate) fn is_active(&self, blinking: bool, now: Instant) -> bool {
+        blinking
+            && !self.interval.is_zero()
+            && now.saturating_duration_since(self.last_input) < self.timeout
+    }
+
+    pub(crate) fn tick(&mut self, blinking: bool, now: Instant) {
+        self.visible = !self.is_active(blinking, now) || !self.visible;
+    }
+
+    pub(crate) fn is_visible(&self, blinking: bool, now: Instant) -> bool {
+        self.visible || !self.is_active(blinking, now)
+    }
+}
+
+/// Resolves the shape to draw, `None` means the cursor is not drawn
+pub(crate) fn cursor_shape(
+    style: CursorStyle,
+    is_focused: bool,
+    is_visible: bool,
+) -> Option<CursorShape> {
+    match style.shape {
+        CursorShape::Hidden => None,
+        _ if !is_focused => Some(CursorShape::HollowBlock),
+        _ if !is_visible => None,
+        shape => Some(shape),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cursor_shape, CursorBlink};
+    use crate::settings::CursorSettings;
+    use alacritty_terminal::vte::ansi::{CursorShape, CursorStyle};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn blink_until_timeout() {
+        let start = Instant::now();
+        let settings = CursorSettings {
+            blinking: true,
+            blink_interval: Duration::from_millis(500),
+            blink_timeout: Duration::from_secs(2),
+            ..Default::default()
+        };
+        let mut blink = CursorBlink::new(&settings, start);
+
+        let now = start + Duration::from_millis(500);
+        assert!(blink.is_active(true, now));
+        blink.tick(true, now);
+        assert!(!blink.is_visible(true, now));
+        assert!(blink.is_visible(false, now));
+        blink.tick(true, now + Duration::from_millis(500));
+        assert!(blink.is_visible(true, now));
+        blink.tick(true, now + Duration::from_millis(1000));
+        assert!(!blink.is_visible(true, now));
+
+        let idle = start + Duration::from_secs(2);
+        assert!(!blink.is_active(true, idle));
+        assert!(blink.is_visible(true, idle));
+        blink.tick(true, idle);
+        assert!(blink.is_visible(true, idle));
+
+        blink.reset(idle);
+        assert!(blink.is_active(true, idle));
+        assert!(!blink.is_active(false, idle));
+    }
+
+    #[test]
+    fn resolve_cursor_shape() {
+        let style = |shape| CursorStyle {
+            shape,
+            blinking: false,
+        };
+
+        assert_eq!(
+            cursor_shape(style(CursorShape::Beam), true, true),
+            Some(CursorShape::Beam)
+        );
+        assert_eq!(
+            cursor_shape(style(CursorShape::Underline), true, true),
+            Some(CursorShape::Underline)
+        );
+        assert_eq!(cursor_shape(style(CursorShape::Beam), true, false), None);
+        assert_eq!(
+            cursor_shape(style(CursorShape::Beam), false, false),
+            Some(CursorShape::HollowBlock)
+        );
+        assert_eq!(cursor_shape(style(CursorShape::Hidden), false, true), None);
+    }
+}
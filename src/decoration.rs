@@ -0,0 +1,174 @@
+use alacritty_terminal::term::cell::Flags;
+use iced::widget::canvas::{Frame, Path, Stroke};
+use iced::{Color, Point, Size};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UnderlineStyle {
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+impl UnderlineStyle {
+    pub(crate) fn from_flags(flags: Flags) -> Option<Self> {
+        if flags.contains(Flags::UNDERCURL) {
+            Some(Self::Curly)
+        } else if flags.contains(Flags::DOUBLE_UNDERLINE) {
+            Some(Self::Double)
+        } else if flags.contains(Flags::DOTTED_UNDERLINE) {
+            Some(Self::Dotted)
+        } else if flags.contains(Flags::DASHED_UNDERLINE) {
+            Some(Self::Dashed)
+        } else if flags.contains(Flags::UNDERLINE) {
+            Some(Self::Single)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum DecorationKind {
+    Underline(UnderlineStyle),
+    Strikeout,
+}
+
+/// The line drawn over a single cell, every style is aligned
+/// to the cell edges, so the lines of the neighbor cells are joined
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Decoration {
+    pub(crate) kind: DecorationKind,
+    pub(crate) position: Point,
+    pub(crate) cell_size: Size,
+    pub(crate) color: Color,
+}
+
+impl Decoration {
+    pub(crate) fn draw(&self, frame: &mut Frame) {
+        let Point { x, y } = self.position;
+        let Size { width, height } = self.cell_size;
+        let thickness = line_thickness(height);
+        let bottom = y + height - thickness;
+
+        match self.kind {
+            DecorationKind::Strikeout => {
+                let y = y + (height - thickness) * 0.5;
+                self.fill_line(frame, x, y, width, thickness);
+            },
+            DecorationKind::Underline(UnderlineStyle::Single) => {
+                self.fill_line(frame, x, bottom, width, thickness);
+            },
+            DecorationKind::Underline(UnderlineStyle::Double) => {
+                self.fill_line(frame, x, bottom, width, thickness);
+                self.fill_line(
+                    frame,
+                    x,
+                    bottom - thickness * 2.0,
+                    width,
+                    thickness,
+                );
+            },
+            DecorationKind::Underline(UnderlineStyle::Curly) => {
+                // One wave per cell, it starts and ends in the middle
+                let amplitude = thickness * 1.5;
+                let middle = y + height - amplitude - thickness * 0.5;
+                let curl = Path::new(|builder| {
+                    builder.move_to(Point::new(x, middle));
+                    builder.quadratic_curve_to(
+                        Point::new(x + width * 0.25, middle - amplitude * 2.0),
+                        Point::new(x + width * 0.5, middle),
+                    );
+                    builder.quadratic_curve_to(
+                        Point::new(x + width * 0.75, middle + amplitude * 2.0),
+                        Point::new(x + width, middle),
+                    );
+                });
+                frame.stroke(
+                    &curl,
+                    Stroke::default()
+                        .with_width(thickness)
+                        .with_color(self.color),
+                );
+            },
+            DecorationKind::Underline(UnderlineStyle::Dotted) => {
+                let dots = (width / (thickness * 2.0)).round().max(1.0);
+                let step = width / dots;
+                for i in 0..dots as usize {
+                    let dot_x = x + step * i as f32 + (step - thickness) * 0.5;
+                    self.fill_line(frame, dot_x, bottom, thickness, thickness);
+                }
+            },
+            DecorationKind::Underline(UnderlineStyle::Dashed) => {
+                // The dash is in the middle of the cell,
+                // so the gaps between the cells are equal to the dashes
+                self.fill_line(
+                    frame,
+                    x + width * 0.25,
+                    bottom,
+                    width * 0.5,
+                    thickness,
+                );
+            },
+        }
+    }
+
+    fn fill_line(
+        &self,
+        frame: &mut Frame,
+        x: f32,
+        y: f32,
+        width: f32,
+        thickness: f32,
+    ) {
+        frame.fill_rectangle(
+            Point::new(x, y),
+            Size::new(width, thickness),
+            self.color,
+        );
+    }
+}
+
+pub(crate) fn line_thickness(cell_height: f32) -> f32 {
+    (cell_height / 16.0).round().max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{line_thickness, UnderlineStyle};
+    use alacritty_terminal::term::cell::Flags;
+
+    #[test]
+    fn underline_style_from_flags() {
+        let cases = [
+            (Flags::empty(), None),
+            (Flags::STRIKEOUT, None),
+            (Flags::UNDERLINE, Some(UnderlineStyle::Single)),
+            (Flags::DOUBLE_UNDERLINE, Some(UnderlineStyle::Double)),
+            (Flags::UNDERCURL, Some(UnderlineStyle::Curly)),
+            (Flags::DOTTED_UNDERLINE, Some(UnderlineStyle::Dotted)),
+            (Flags::DASHED_UNDERLINE, Some(UnderlineStyle::Dashed)),
+            (
+                Flags::UNDERLINE | Flags::UNDERCURL | Flags::BOLD,
+                Some(UnderlineStyle::Curly),
+            ),
+        ];
+        for (flags, expected) in cases {
+            assert_eq!(
+                UnderlineStyle::from_flags(flags),
+                expected,
+                "{:?}",
+                flags
+            );
+        }
+    }
+
+    #[test]
+    fn line_thickness_scales_with_cell() {
+        assert_eq!(line_thickness(8.0), 1.0);
+        assert_eq!(line_thickness(18.0), 1.0);
+        assert_eq!(line_thickness(36.0), 2.0);
+        assert_eq!(line_thickness(64.0), 4.0);
+    }
+}
@@ -1,26 +1,66 @@
 use crate::settings::FontSettings;
+use alacritty_terminal::term::cell;
+use iced::font::{Style, Weight};
 use iced::{Font, Size};
 use iced_core::{
     alignment::Vertical,
     text::{Alignment, LineHeight, Paragraph, Shaping as TextShaping},
     Text,
 };
-use iced_graphics::text::paragraph;
+use iced_graphics::text::cosmic_text::fontdb;
+use iced_graphics::text::{font_system, paragraph, to_attributes};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+const FONT_SIZE_STEP: f32 = 1.0;
+const MIN_FONT_SIZE: f32 = 4.0;
 
 #[derive(Debug, Clone)]
 pub struct TermFont {
     pub(crate) size: f32,
+    default_size: f32,
     pub(crate) font_type: Font,
+    /// The regular, bold, italic and bold italic faces
+    faces: [Font; 4],
+    fallback: Vec<Font>,
+    /// The fonts found for the glyphs of the faces
+    glyph_fonts: Arc<Mutex<HashMap<(char, usize), Font>>>,
     pub(crate) scale_factor: f32,
     pub(crate) measure: Size<f32>,
+    pub(crate) builtin_box_drawing: bool,
 }
 
 impl TermFont {
     pub fn new(settings: FontSettings) -> Self {
+        let regular = settings.font_type;
+        let bold = settings.bold.unwrap_or(Font {
+            weight: Weight::Bold,
+            ..regular
+        });
+        let italic = settings.italic.unwrap_or(Font {
+            style: Style::Italic,
+            ..regular
+        });
+        let bold_italic = settings
+            .bold_italic
+            .or(settings.bold.map(|bold| Font {
+                style: Style::Italic,
+                ..bold
+            }))
+            .unwrap_or(Font {
+                weight: Weight::Bold,
+                ..italic
+            });
+
         Self {
             size: settings.size,
+            default_size: settings.size,
             font_type: settings.font_type,
+            faces: [regular, bold, italic, bold_italic],
+            fallback: settings.fallback,
+            glyph_fonts: Arc::default(),
             scale_factor: settings.scale_factor,
+            builtin_box_drawing: settings.builtin_box_drawing,
             measure: font_measure(
                 settings.size,
                 settings.scale_factor,
@@ -29,10 +69,77 @@ impl TermFont {
         }
     }
 
+    pub fn increase_size(&mut self) {
+        self.size += FONT_SIZE_STEP;
+    }
+
+    pub fn decrease_size(&mut self) {
+        self.size = (self.size - FONT_SIZE_STEP).max(MIN_FONT_SIZE);
+    }
+
+    /// Restores the size the font was created with
+    pub fn reset_size(&mut self) {
+        self.size = self.default_size;
+    }
+
     pub fn sync(&mut self) {
         self.measure =
             font_measure(self.size, self.scale_factor, self.font_type)
     }
+
+    /// Returns the face of the text with the cell flags
+    pub(crate) fn face(&self, flags: cell::Flags) -> Font {
+        self.faces[face_index(flags)]
+    }
+
+    /// Returns the first font of the face and the fallback ones
+    /// that has the glyph, the face is used if none of them has it
+    pub(crate) fn glyph_font(&self, c: char, flags: cell::Flags) -> Font {
+        let index = face_index(flags);
+        let face = self.faces[index];
+        if c.is_ascii() || self.fallback.is_empty() {
+            return face;
+        }
+
+        let mut glyph_fonts =
+            self.glyph_fonts.lock().expect("glyph fonts are poisoned");
+        *glyph_fonts.entry((c, index)).or_insert_with(|| {
+            // The fallback fonts keep the weight and style of the face
+            let fallback = self.fallback.iter().map(|font| Font {
+                weight: face.weight,
+                style: face.style,
+                ..*font
+            });
+            std::iter::once(face)
+                .chain(fallback)
+                .find(|font| has_glyph(*font, c))
+                .unwrap_or(face)
+        })
+    }
+}
+
+fn face_index(flags: cell::Flags) -> usize {
+    flags.contains(cell::Flags::BOLD) as usize
+        | (flags.contains(cell::Flags::ITALIC) as usize) << 1
+}
+
+fn has_glyph(font: Font, c: char) -> bool {
+    let attrs = to_attributes(font);
+    let mut font_system =
+        font_system().write().expect("font system is poisoned");
+    let font_system = font_system.raw();
+    let query = fontdb::Query {
+        families: &[attrs.family],
+        weight: attrs.weight,
+        stretch: attrs.stretch,
+        style: attrs.style,
+    };
+
+    font_system
+        .db()
+        .query(&query)
+        .and_then(|id| font_system.get_font(id, attrs.weight))
+        .is_some_and(|font| font.as_swash().charmap().map(c) != 0)
 }
 
 fn font_measure(
@@ -54,3 +161,56 @@ fn font_measure(
 
     paragraph.min_bounds()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ICONS: Font = Font::with_name("Iced-Icons");
+    const ARROW_ICON: char = '\u{e800}';
+
+    #[test]
+    fn derive_missing_faces() {
+        let bold = Font::with_name("Bold");
+        let font = TermFont::new(FontSettings {
+            bold: Some(bold),
+            ..Default::default()
+        });
+
+        let italic = cell::Flags::ITALIC;
+        assert_eq!(font.face(cell::Flags::empty()), Font::MONOSPACE);
+        assert_eq!(font.face(cell::Flags::BOLD), bold);
+        assert_eq!(font.face(italic).style, Style::Italic);
+        assert_eq!(font.face(italic).weight, Weight::Normal);
+        assert_eq!(
+            font.face(cell::Flags::BOLD_ITALIC),
+            Font {
+                style: Style::Italic,
+                ..bold
+            }
+        );
+    }
+
+    #[test]
+    fn find_glyph_in_fallback_fonts() {
+        let font = TermFont::new(FontSettings {
+            fallback: vec![Font::with_name("Missing Font"), ICONS],
+            ..Default::default()
+        });
+
+        assert_eq!(font.glyph_font(ARROW_ICON, cell::Flags::empty()), ICONS);
+        // The fallback font keeps the weight of the face
+        assert_eq!(
+            font.glyph_font(ARROW_ICON, cell::Flags::BOLD),
+            Font {
+                weight: Weight::Bold,
+                ..ICONS
+            }
+        );
+        assert_eq!(font.glyph_font('a', cell::Flags::empty()), Font::MONOSPACE);
+        assert_eq!(
+            font.glyph_font('\u{10fff0}', cell::Flags::empty()),
+            Font::MONOSPACE
+        );
+    }
+}
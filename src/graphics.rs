@@ -0,0 +1,1436 @@
+use crate::backend::{EventProxy, GridSnapshot};
+#[cfg(feature = "kitty-graphics")]
+use crate::kitty::{KittyGraphics, MAX_COMMAND_LEN};
+#[cfg(feature = "sixel")]
+use crate::sixel::SixelDecoder;
+use alacritty_terminal::event::{Event, EventListener};
+use alacritty_terminal::event::{OnResize, WindowSize};
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::Line;
+use alacritty_terminal::sync::FairMutex;
+use alacritty_terminal::term::cell::Hyperlink;
+use alacritty_terminal::term::Term;
+#[cfg(windows)]
+use alacritty_terminal::tty::PTY_READ_WRITE_TOKEN;
+use alacritty_terminal::tty::{ChildEvent, EventedPty, EventedReadWrite};
+use alacritty_terminal::vte::ansi::Handler;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use iced::advanced::image::Handle;
+use iced::{Rectangle, Size};
+#[cfg(windows)]
+use polling::os::iocp::{CompletionPacket, PollerIocpExt};
+use polling::{Event as PollingEvent, PollMode, Poller};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, Read};
+use std::ops::Range;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The images are removed from the oldest one above this size
+const MAX_STORE_BYTES: usize = 128 * 1024 * 1024;
+const MARKER_SCHEME: &str = "iced-term-image:";
+const FENCE_SCHEME: &str = "iced-term-fence:";
+/// The output is resumed without the markers after it
+const FENCE_TIMEOUT: Duration = Duration::from_secs(1);
+/// The key of the PTY reads, it's private on unix
+#[cfg(unix)]
+const PTY_READ_WRITE_TOKEN: usize = 0;
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+#[cfg(feature = "sixel")]
+const MAX_PARAMS_LEN: usize = 64;
+
+#[derive(Debug, Clone)]
+pub(crate) struct StoredImage {
+    pub(crate) handle: Handle,
+    pub(crate) width: f32,
+    pub(crate) height: f32,
+    size: usize,
+}
+
+/// The image shown from the cell, it is drawn over the text
+/// unless `z_index` is negative
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Placement {
+    pub(crate) image_id: u32,
+    /// The shown part of the image in its pixels
+    pub(crate) source: Rectangle,
+    /// The offset from the cell and the shown size in pixels
+    pub(crate) bounds: Rectangle,
+    /// The cell height the image rows were laid out with
+    pub(crate) cell_height: f32,
+    pub(crate) z_index: i32,
+    /// The marked columns of each row, they are clipped to the screen
+    pub(crate) columns: usize,
+    /// The row of the image that is a part of the text is removed once
+    /// any of its cells is overwritten, the other images are removed
+    /// once all of their cells are
+    pub(crate) is_text: bool,
+}
+
+impl Placement {
+    /// Shows the whole image at its size as a part of the text
+    #[cfg(feature = "sixel")]
+    pub(crate) fn new(image_id: u32, size: Size, cell: Size) -> Self {
+        let bounds = Rectangle::with_size(size);
+        Self {
+            image_id,
+            source: bounds,
+            bounds,
+            cell_height: cell.height,
+            z_index: 0,
+            columns: (size.width / cell.width).ceil().max(1.0) as usize,
+            is_text: true,
+        }
+    }
+
+    /// The number of rows the placement takes
+    pub(crate) fn rows(&self) -> usize {
+        let height = self.bounds.y + self.bounds.height;
+        (height / self.cell_height).ceil().max(1.0) as usize
+    }
+
+    /// Returns the bounds of the whole image and the clip bounds
+    /// of the placement row shown on the viewport
+    pub(crate) fn row_bounds(
+        &self,
+        image: &StoredImage,
+        row: &ImagePlacement,
+        viewport: Rectangle,
+        cell: Size,
+    ) -> Option<(Rectangle, Rectangle)> {
+        // The placement keeps its rows when the font size is changed
+        let scale = cell.height / self.cell_height;
+        let line_y = viewport.y + row.line as f32 * cell.height;
+        let shown = Rectangle {
+            x: viewport.x
+                + row.column as f32 * cell.width
+                + self.bounds.x * scale,
+            y: line_y - row.row as f32 * cell.height + self.bounds.y * scale,
+            width: self.bounds.width * scale,
+            height: self.bounds.height * scale,
+        };
+        let scale_x = shown.width / self.source.width;
+        let scale_y = shown.height / self.source.height;
+        let image_bounds = Rectangle {
+            x: shown.x - self.source.x * scale_x,
+            y: shown.y - self.source.y * scale_y,
+            width: image.width * scale_x,
+            height: image.height * scale_y,
+        };
+        let row_bounds = Rectangle {
+            y: line_y,
+            height: cell.height,
+            ..shown
+        };
+        let clip_bounds =
+            row_bounds.intersection(&shown)?.intersection(&viewport)?;
+
+        Some((image_bounds, clip_bounds))
+    }
+}
+
+/// The random part of the marker hyperlinks,
+/// the applications can't forge the markers without it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Nonce(u64);
+
+impl Default for Nonce {
+    fn default() -> Self {
+        Self(RandomState::new().build_hasher().finish())
+    }
+}
+
+/// The images shown by the terminal and their placements
+/// that are referenced by the cells
+#[derive(Debug, Default)]
+pub(crate) struct GraphicsStore {
+    nonce: Nonce,
+    images: HashMap<u32, StoredImage>,
+    placements: HashMap<u32, Placement>,
+    order: VecDeque<u32>,
+    size: usize,
+    next_image_id: u32,
+    next_placement_id: u32,
+    /// Changed with the images or placements
+    #[cfg(feature = "kitty-graphics")]
+    version: u64,
+    /// Set when the markers are attached, the terminal doesn't damage
+    /// the cells for it
+    is_marked: bool,
+}
+
+impl GraphicsStore {
+    pub(crate) fn image(&self, id: u32) -> Option<&StoredImage> {
+        self.images.get(&id)
+    }
+
+    pub(crate) fn placement(&self, id: u32) -> Option<&Placement> {
+        self.placements.get(&id)
+    }
+
+    /// Stores the RGBA pixels, the oldest images are removed
+    /// when the store is full
+    pub(crate) fn insert_image(
+        &mut self,
+        width: u32,
+        height: u32,
+        pixels: Vec<u8>,
+    ) -> u32 {
+        let id = self.next_image_id;
+        self.next_image_id = self.next_image_id.wrapping_add(1);
+        #[cfg(feature = "kitty-graphics")]
+        {
+            self.version += 1;
+        }
+
+        let size = pixels.len();
+        self.size += size;
+        self.order.push_back(id);
+        self.images.insert(
+            id,
+            StoredImage {
+                handle: Handle::from_rgba(width, height, pixels),
+                width: width as f32,
+                height: height as f32,
+                size,
+            },
+        );
+
+        while self.size > MAX_STORE_BYTES && self.order.len() > 1 {
+            if let Some(id) = self.order.front().copied() {
+                self.remove_image(id);
+            }
+        }
+
+        id
+    }
+
+    /// Removes the image with its placements
+    pub(crate) fn remove_image(&mut self, id: u32) {
+        if let Some(image) = self.images.remove(&id) {
+            #[cfg(feature = "kitty-graphics")]
+            {
+                self.version += 1;
+            }
+            self.size -= image.size;
+            self.order.retain(|&image_id| image_id != id);
+            self.placements
+                .retain(|_, placement| placement.image_id != id);
+        }
+    }
+
+    pub(crate) fn insert_placement(&mut self, placement: Placement) -> u32 {
+        let id = self.next_placement_id;
+        self.next_placement_id = self.next_placement_id.wrapping_add(1);
+        #[cfg(feature = "kitty-graphics")]
+        {
+            self.version += 1;
+        }
+        self.placements.insert(id, placement);
+        id
+    }
+
+    #[cfg(feature = "kitty-graphics")]
+    pub(crate) fn remove_placement(&mut self, id: u32) {
+        if self.placements.remove(&id).is_some() {
+            self.version += 1;
+        }
+    }
+
+    #[cfg(feature = "kitty-graphics")]
+    pub(crate) fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns whether the markers were attached since the last call
+    pub(crate) fn take_marked(&mut self) -> bool {
+        std::mem::take(&mut self.is_marked)
+    }
+
+    /// The placement rows are attached to the cells with the hyperlinks,
+    /// so they scroll with the text and are cleared when the cells
+    /// are overwritten
+    fn marker_uri(
+        &self,
+        placement_id: u32,
+        row: usize,
+        column: usize,
+    ) -> String {
+        format!(
+            "{}{:x}/{}/{}/{}",
+            MARKER_SCHEME, self.nonce.0, placement_id, row, column
+        )
+    }
+
+    /// Returns the placement id, row and column of the marker hyperlink
+    pub(crate) fn parse_marker(
+        &self,
+        uri: &str,
+    ) -> Option<(u32, usize, usize)> {
+        let (nonce, marker) =
+            uri.strip_prefix(MARKER_SCHEME)?.split_once('/')?;
+        if u64::from_str_radix(nonce, 16).ok()? != self.nonce.0 {
+            return None;
+        }
+        let mut marker = marker.split('/');
+        let placement_id = marker.next()?.parse().ok()?;
+        let row = marker.next()?.parse().ok()?;
+        let column = marker.next()?.parse().ok()?;
+        marker
+            .next()
+            .is_none()
+            .then_some((placement_id, row, column))
+    }
+}
+
+/// The placement row shown on the viewport cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ImagePlacement {
+    pub(crate) placement_id: u32,
+    pub(crate) row: usize,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+/// Collects the image rows attached to the viewport cells
+pub(crate) fn image_placements(
+    grid: &GridSnapshot,
+    store: &GraphicsStore,
+    placements: &mut Vec<ImagePlacement>,
+) {
+    placements.clear();
+    let mut rows: Vec<(ImagePlacement, usize)> = Vec::new();
+    for line in 0..grid.screen_lines() {
+        let cells = &grid[Line(line as i32 - grid.display_offset() as i32)];
+        // The marked cells of the rows are counted
+        rows.clear();
+        for (column, cell) in cells.iter().enumerate() {
+            let Some((placement_id, row, offset)) = cell
+                .hyperlink()
+                .and_then(|hyperlink| store.parse_marker(hyperlink.uri()))
+            else {
+                continue;
+            };
+            let Some(column) = column.checked_sub(offset) else {
+                continue;
+            };
+            let row = ImagePlacement {
+                placement_id,
+                row,
+                line,
+                column,
+            };
+            match rows.last_mut() {
+                Some((last, count)) if *last == row => *count += 1,
+                _ => rows.push((row, 1)),
+            }
+        }
+
+        for &(row, count) in &rows {
+            let Some(placement) = store.placement(row.placement_id) else {
+                continue;
+            };
+            if placement.is_text {
+                if count == placement.columns {
+                    placements.push(row);
+                }
+                continue;
+            }
+
+            // The rows of the other images are placed from any marked cell
+            if placements
+                .iter()
+                .any(|shown| shown.placement_id == row.placement_id)
+            {
+                continue;
+            }
+            for placement_row in 0..placement.rows() {
+                let Some(line) =
+                    (row.line + placement_row).checked_sub(row.row)
+                else {
+                    continue;
+                };
+                if line < grid.screen_lines() {
+                    placements.push(ImagePlacement {
+                        row: placement_row,
+                        line,
+                        ..row
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// The placement waiting for the terminal to reach the cursor position
+/// of its image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Anchor {
+    pub(crate) placement_id: u32,
+    pub(crate) cursor: CursorMovement,
+}
+
+impl Anchor {
+    /// Marks the placement cells from the cursor down, the text is kept
+    fn attach<L: EventListener>(
+        self,
+        term: &mut Term<L>,
+        store: &mut GraphicsStore,
+    ) {
+        let start = term.grid().cursor.point;
+        let Some(placement) = store.placements.get_mut(&self.placement_id)
+        else {
+            return;
+        };
+        placement.columns =
+            placement.columns.min(term.columns() - start.column.0);
+        let placement = *placement;
+
+        for row in 0..placement.rows() {
+            let line = match self.cursor {
+                // The rows below the screen are not marked without scrolling
+                #[cfg(feature = "kitty-graphics")]
+                CursorMovement::None => {
+                    let line = start.line + row;
+                    if line.0 >= term.screen_lines() as i32 {
+                        break;
+                    }
+                    line
+                },
+                _ => {
+                    if row > 0 {
+                        term.linefeed();
+                    }
+                    term.grid().cursor.point.line
+                },
+            };
+            for column in 0..placement.columns {
+                let uri = store.marker_uri(self.placement_id, row, column);
+                let hyperlink = Hyperlink::new(None::<String>, uri);
+                term.grid_mut()[line][start.column + column]
+                    .set_hyperlink(Some(hyperlink));
+            }
+        }
+
+        match self.cursor {
+            #[cfg(feature = "sixel")]
+            CursorMovement::NextLine => term.linefeed(),
+            #[cfg(feature = "kitty-graphics")]
+            CursorMovement::AfterPlacement(columns) => {
+                term.move_forward(columns)
+            },
+            #[cfg(feature = "kitty-graphics")]
+            CursorMovement::None => {},
+        }
+        store.is_marked = true;
+    }
+}
+
+/// Where the cursor is left after the placement is marked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CursorMovement {
+    /// On the line below the placement, in the column it started at
+    #[cfg(feature = "sixel")]
+    NextLine,
+    /// After the last column of the placement on its last row
+    #[cfg(feature = "kitty-graphics")]
+    AfterPlacement(usize),
+    /// At the first cell of the placement, the screen is not scrolled
+    #[cfg(feature = "kitty-graphics")]
+    None,
+}
+
+/// The mark the PTY output is paused at until the terminal parses it,
+/// so the image markers are attached at the cursor position of the image.
+/// It's the OSC 52 copy, the terminal reports its text right away.
+#[derive(Debug, Default)]
+pub(crate) struct Fence {
+    nonce: Nonce,
+    /// The number of the last fence reported by the terminal
+    parsed: AtomicU64,
+}
+
+impl Fence {
+    fn text(&self, number: u64) -> String {
+        format!("{}{:x}/{}", FENCE_SCHEME, self.nonce.0, number)
+    }
+
+    /// The synchronized update is ended, otherwise the terminal
+    /// would not parse the fence until the update is
+    fn sequence(&self, number: u64) -> Vec<u8> {
+        let text = BASE64.encode(self.text(number));
+        format!("\x1b[?2026l\x1b]52;s;{}\x1b\\", text).into_bytes()
+    }
+
+    /// Records the fence copied by the terminal,
+    /// returns `false` if the copied text is not a fence
+    pub(crate) fn receive(&self, text: &str) -> bool {
+        let Some(number) = text
+            .strip_prefix(FENCE_SCHEME)
+            .and_then(|text| text.split_once('/'))
+            .filter(|(nonce, _)| {
+                u64::from_str_radix(nonce, 16).ok() == Some(self.nonce.0)
+            })
+            .and_then(|(_, number)| number.parse().ok())
+        else {
+            return false;
+        };
+
+        self.parsed.store(number, Ordering::Release);
+        true
+    }
+
+    fn is_parsed(&self, number: u64) -> bool {
+        self.parsed.load(Ordering::Acquire) == number
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterState {
+    Ground,
+    Escape,
+    #[cfg(feature = "sixel")]
+    DcsParams,
+    #[cfg(feature = "sixel")]
+    Sixel,
+    #[cfg(feature = "sixel")]
+    SixelEscape,
+    #[cfg(feature = "kitty-graphics")]
+    Apc,
+    #[cfg(feature = "kitty-graphics")]
+    Kitty,
+    #[cfg(feature = "kitty-graphics")]
+    KittyEscape,
+    String,
+    StringEscape,
+}
+
+/// Takes the graphics sequences out of the PTY output,
+/// the rest of the output is passed to the terminal as is
+struct GraphicsFilter {
+    state: FilterState,
+    sequence: Vec<u8>,
+    #[cfg(feature = "sixel")]
+    decoder: Option<SixelDecoder>,
+    #[cfg(feature = "kitty-graphics")]
+    kitty: KittyGraphics,
+    /// The placement of the last image, the output is paused at it
+    anchor: Option<Anchor>,
+    cell_width: u16,
+    cell_height: u16,
+    store: Arc<Mutex<GraphicsStore>>,
+    event_proxy: EventProxy,
+}
+
+impl GraphicsFilter {
+    fn new(store: Arc<Mutex<GraphicsStore>>, event_proxy: EventProxy) -> Self {
+        Self {
+            state: FilterState::Ground,
+            sequence: Vec::new(),
+            #[cfg(feature = "sixel")]
+            decoder: None,
+            #[cfg(feature = "kitty-graphics")]
+            kitty: KittyGraphics::default(),
+            anchor: None,
+            cell_width: 1,
+            cell_height: 1,
+            store,
+            event_proxy,
+        }
+    }
+
+    fn cell_size(&self) -> Size {
+        Size::new(
+            self.cell_width.max(1) as f32,
+            self.cell_height.max(1) as f32,
+        )
+    }
+
+    /// Filters the input until the end or the next image placement,
+    /// returns the number of the processed bytes
+    fn process(&mut self, input: &[u8], output: &mut Vec<u8>) -> usize {
+        let mut bytes = input.iter().copied().enumerate();
+        while let Some((index, byte)) = bytes.next() {
+            match self.state {
+                FilterState::Ground => {
+                    // Plain text is copied until the next escape
+                    let rest = &input[index..];
+                    let end = rest
+                        .iter()
+                        .position(|&byte| byte == ESC)
+                        .unwrap_or(rest.len());
+                    output.extend_from_slice(&rest[..end]);
+                    if end < rest.len() {
+                        self.state = FilterState::Escape;
+                    }
+                    if end > 0 {
+                        bytes.nth(end - 1);
+                    }
+                },
+                FilterState::Escape => self.process_escape(byte, output),
+                #[cfg(feature = "sixel")]
+                FilterState::DcsParams => self.process_dcs_params(byte, output),
+                #[cfg(feature = "sixel")]
+                FilterState::Sixel => match byte {
+                    ESC => self.state = FilterState::SixelEscape,
+                    _ => {
+                        if let Some(decoder) = &mut self.decoder {
+                            decoder.put(byte);
+                        }
+                    },
+                },
+                #[cfg(feature = "sixel")]
+                FilterState::SixelEscape => {
+                    self.finish_sixel();
+                    if byte == b'\\' {
+                        self.state = FilterState::Ground;
+                    } else if self.anchor.is_some() {
+                        // The next sequence is started after the pause
+                        self.state = FilterState::Escape;
+                        return index;
+                    } else {
+                        self.process_escape(byte, output);
+                    }
+                },
+                #[cfg(feature = "kitty-graphics")]
+                FilterState::Apc => {
+                    if byte == b'G' {
+                        self.state = FilterState::Kitty;
+                    } else {
+                        output.extend_from_slice(&[ESC, b'_']);
+                        self.state = FilterState::String;
+                        self.process(&[byte], output);
+                    }
+                },
+                #[cfg(feature = "kitty-graphics")]
+                FilterState::Kitty => {
+                    // The payload is copied until the string terminator
+                    let rest = &input[index..];
+                    let end = rest
+                        .iter()
+                        .position(|&byte| byte == ESC)
+                        .unwrap_or(rest.len());
+                    let len = end.min(MAX_COMMAND_LEN - self.sequence.len());
+                    self.sequence.extend_from_slice(&rest[..len]);
+                    if end < rest.len() {
+                        self.state = FilterState::KittyEscape;
+                    }
+                    if end > 0 {
+                        bytes.nth(end - 1);
+                    }
+                },
+                #[cfg(feature = "kitty-graphics")]
+                FilterState::KittyEscape => {
+                    self.finish_kitty();
+                    if byte == b'\\' {
+                        self.state = FilterState::Ground;
+                    } else if self.anchor.is_some() {
+                        self.state = FilterState::Escape;
+                        return index;
+                    } else {
+                        self.process_escape(byte, output);
+                    }
+                },
+                FilterState::String => match byte {
+                    ESC => self.state = FilterState::StringEscape,
+                    BEL => {
+                        output.push(byte);
+                        self.state = FilterState::Ground;
+                    },
+                    _ => output.push(byte),
+                },
+                // The escape ends the string, it may start the next sequence
+                FilterState::StringEscape => {
+                    if byte == b'\\' {
+                        output.extend_from_slice(&[ESC, byte]);
+                        self.state = FilterState::Ground;
+                    } else {
+                        self.process_escape(byte, output);
+                    }
+                },
+            }
+
+            if self.anchor.is_some() {
+                return index + 1;
+            }
+        }
+
+        input.len()
+    }
+
+    fn process_escape(&mut self, byte: u8, output: &mut Vec<u8>) {
+        self.sequence.clear();
+        self.state = match byte {
+            #[cfg(feature = "sixel")]
+            b'P' => FilterState::DcsParams,
+            #[cfg(feature = "kitty-graphics")]
+            b'_' => FilterState::Apc,
+            ESC => {
+                output.push(ESC);
+                FilterState::Escape
+            },
+            // The APC string is taken by the kitty graphics if it's enabled
+            #[allow(unreachable_patterns)]
+            b']' | b'_' | b'^' | b'X' => {
+                output.extend_from_slice(&[ESC, byte]);
+                FilterState::String
+            },
+            _ => {
+                output.extend_from_slice(&[ESC, byte]);
+                FilterState::Ground
+            },
+        };
+    }
+
+    #[cfg(feature = "sixel")]
+    fn process_dcs_params(&mut self, byte: u8, output: &mut Vec<u8>) {
+        if byte == ESC || self.sequence.len() >= MAX_PARAMS_LEN {
+            self.flush_sequence(b'P', output);
+            self.state = FilterState::String;
+            self.process(&[byte], output);
+            return;
+        }
+
+        match byte {
+            b'q' if self
+                .sequence
+                .iter()
+                .all(|b| b.is_ascii_digit() || *b == b';') =>
+            {
+                let params: Vec<u32> = self
+                    .sequence
+                    .split(|&b| b == b';')
+                    .map(|param| {
+                        std::str::from_utf8(param)
+                            .ok()
+                            .and_then(|param| param.parse().ok())
+                            .unwrap_or(0)
+                    })
+                    .collect();
+                self.decoder = Some(SixelDecoder::new(&params));
+                self.sequence.clear();
+                self.state = FilterState::Sixel;
+            },
+            0x40..=0x7e => {
+                self.sequence.push(byte);
+                self.flush_sequence(b'P', output);
+                self.state = FilterState::String;
+            },
+            _ => self.sequence.push(byte),
+        }
+    }
+
+    #[cfg(feature = "sixel")]
+    fn flush_sequence(&mut self, introducer: u8, output: &mut Vec<u8>) {
+        output.extend_from_slice(&[ESC, introducer]);
+        output.append(&mut self.sequence);
+    }
+
+    /// Stores the image, its rows are marked from the cursor down
+    /// and the cursor is left below the image
+    #[cfg(feature = "sixel")]
+    fn finish_sixel(&mut self) {
+        let Some(image) = self.decoder.take().and_then(SixelDecoder::finish)
+        else {
+            return;
+        };
+
+        let size = Size::new(image.width as f32, image.height as f32);
+        let cell = self.cell_size();
+        let mut store = self.store.lock().expect("graphics store is poisoned");
+        let image_id = store.insert_image(
+            image.width as u32,
+            image.height as u32,
+            image.pixels,
+        );
+        let placement = Placement::new(image_id, size, cell);
+        self.anchor = Some(Anchor {
+            placement_id: store.insert_placement(placement),
+            cursor: CursorMovement::NextLine,
+        });
+    }
+
+    /// Runs the kitty graphics command, its reply is written to the PTY.
+    /// The command may change the store without any output to the terminal,
+    /// so the terminal is woken up to draw the change
+    #[cfg(feature = "kitty-graphics")]
+    fn finish_kitty(&mut self) {
+        let command = std::mem::take(&mut self.sequence);
+        let cell = self.cell_size();
+        let mut store = self.store.lock().expect("graphics store is poisoned");
+        let version = store.version();
+        if let Some(reply) =
+            self.kitty
+                .process(&command, cell, &mut store, &mut self.anchor)
+        {
+            self.event_proxy.send_event(Event::PtyWrite(reply));
+        }
+        if store.version() != version {
+            self.event_proxy.send_event(Event::Wakeup);
+        }
+    }
+}
+
+/// Wakes the event loop up to read the PTY again
+#[derive(Default)]
+struct Waker {
+    #[cfg(unix)]
+    sockets: Option<(UnixStream, UnixStream)>,
+    #[cfg(windows)]
+    poller: Option<Arc<Poller>>,
+}
+
+impl Waker {
+    fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            let (reader, writer) = UnixStream::pair()?;
+            reader.set_nonblocking(true)?;
+            writer.set_nonblocking(true)?;
+            let interest = PollingEvent::readable(PTY_READ_WRITE_TOKEN);
+            unsafe { poll.add_with_mode(&reader, interest, mode)? };
+            self.sockets = Some((reader, writer));
+        }
+        #[cfg(windows)]
+        {
+            let _ = mode;
+            self.poller = Some(poll.clone());
+        }
+        Ok(())
+    }
+
+    fn reregister(&self, poll: &Arc<Poller>, mode: PollMode) -> io::Result<()> {
+        #[cfg(unix)]
+        if let Some((reader, _)) = &self.sockets {
+            let interest = PollingEvent::readable(PTY_READ_WRITE_TOKEN);
+            poll.modify_with_mode(reader, interest, mode)?;
+        }
+        #[cfg(windows)]
+        let _ = (poll, mode);
+        Ok(())
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        #[cfg(unix)]
+        if let Some((reader, _)) = self.sockets.take() {
+            poll.delete(&reader)?;
+        }
+        #[cfg(windows)]
+        {
+            let _ = poll;
+            self.poller = None;
+        }
+        Ok(())
+    }
+
+    fn wake(&self) {
+        #[cfg(unix)]
+        if let Some((_, writer)) = &self.sockets {
+            use std::io::Write;
+
+            // The socket is readable while its buffer is full too
+            let mut writer = writer;
+            let _ = writer.write(&[0]);
+        }
+        #[cfg(windows)]
+        if let Some(poller) = &self.poller {
+            let event = PollingEvent::readable(PTY_READ_WRITE_TOKEN);
+            let _ = poller.post(CompletionPacket::new(event));
+        }
+    }
+
+    fn drain(&self) {
+        #[cfg(unix)]
+        if let Some((reader, _)) = &self.sockets {
+            let mut reader = reader;
+            let mut buf = [0; 64];
+            while matches!(reader.read(&mut buf), Ok(len) if len > 0) {}
+        }
+    }
+}
+
+/// The output is paused at the image until its markers are attached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pause {
+    None,
+    /// The output before the image and the fence are passed to the terminal
+    Fence(Anchor, Instant),
+    /// The terminal has parsed the fence
+    Attach(Anchor),
+}
+
+/// The PTY that passes its output through the graphics filter.
+///
+/// The event loop of the terminal parses the read output with the terminal
+/// locked, so the markers of the images are attached at the start of its
+/// next read, once the terminal has parsed everything before the image.
+pub(crate) struct GraphicsPty<T> {
+    pty: T,
+    filter: GraphicsFilter,
+    input: Box<[u8]>,
+    /// The read input that is not filtered yet
+    pending: Range<usize>,
+    output: Vec<u8>,
+    position: usize,
+    term: Arc<FairMutex<Term<EventProxy>>>,
+    fence: Arc<Fence>,
+    /// The number of the last fence
+    fences: u64,
+    pause: Pause,
+    waker: Waker,
+}
+
+impl<T> GraphicsPty<T> {
+    pub(crate) fn new(
+        pty: T,
+        store: Arc<Mutex<GraphicsStore>>,
+        term: Arc<FairMutex<Term<EventProxy>>>,
+        event_proxy: EventProxy,
+    ) -> Self {
+        Self {
+            pty,
+            fence: event_proxy.fence(),
+            filter: GraphicsFilter::new(store, event_proxy),
+            input: vec![0; 0x1_0000].into_boxed_slice(),
+            pending: 0..0,
+            output: Vec::new(),
+            position: 0,
+            term,
+            fences: 0,
+            pause: Pause::None,
+            waker: Waker::default(),
+        }
+    }
+
+    /// Returns whether the output is resumed
+    fn resume(&mut self) -> bool {
+        match self.pause {
+            Pause::None => true,
+            // The event loop may hold the terminal lock until the read
+            // is blocked, so it's woken up to read again
+            Pause::Fence(anchor, _) if self.fence.is_parsed(self.fences) => {
+                self.pause = Pause::Attach(anchor);
+                self.waker.wake();
+                false
+            },
+            // The terminal may never parse the fence if the application
+            // has left it in a string sequence
+            Pause::Fence(_, since) => {
+                if since.elapsed() < FENCE_TIMEOUT {
+                    return false;
+                }
+                self.waker.drain();
+                self.pause = Pause::None;
+                true
+            },
+            Pause::Attach(anchor) => {
+                self.waker.drain();
+                let mut term = self.term.lock_unfair();
+                let mut store = self
+                    .filter
+                    .store
+                    .lock()
+                    .expect("graphics store is poisoned");
+                anchor.attach(&mut term, &mut store);
+                drop(store);
+                drop(term);
+
+                self.filter.event_proxy.send_event(Event::Wakeup);
+                self.pause = Pause::None;
+                true
+            },
+        }
+    }
+}
+
+impl<T: EventedReadWrite> Read for GraphicsPty<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // The filtered output may be empty while the sequence is not ended
+        while self.position == self.output.len() {
+            if !self.resume() {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+
+            if self.pending.is_empty() {
+                let read = self.pty.reader().read(&mut self.input)?;
+                if read == 0 {
+                    return Ok(0);
+                }
+                self.pending = 0..read;
+            }
+
+            self.output.clear();
+            self.position = 0;
+            let input = &self.input[self.pending.clone()];
+            self.pending.start += self.filter.process(input, &mut self.output);
+            if let Some(anchor) = self.filter.anchor.take() {
+                self.fences += 1;
+                let fence = self.fence.sequence(self.fences);
+                self.output.extend_from_slice(&fence);
+                self.pause = Pause::Fence(anchor, Instant::now());
+                // The event loop may stop reading once the fence is parsed
+                self.waker.wake();
+            }
+        }
+
+        let pending = &self.output[self.position..];
+        let len = pending.len().min(buf.len());
+        buf[..len].copy_from_slice(&pending[..len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+impl<T: EventedReadWrite> EventedReadWrite for GraphicsPty<T> {
+    type Reader = Self;
+    type Writer = T::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.waker.register(poll, mode)?;
+        unsafe { self.pty.register(poll, interest, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.waker.reregister(poll, mode)?;
+        self.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.waker.deregister(poll)?;
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl<T: EventedPty> EventedPty for GraphicsPty<T> {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl<T: OnResize> OnResize for GraphicsPty<T> {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.filter.cell_width = window_size.cell_width;
+        self.filter.cell_height = window_size.cell_height;
+        self.pty.on_resize(window_size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{event_channel, Damage};
+    use alacritty_terminal::index::Column;
+    use alacritty_terminal::term::{test::TermSize, Config};
+    use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+    use iced::Point;
+
+    /// Returns the output and the anchors with the output length at them
+    fn filter(
+        input: &[&[u8]],
+    ) -> (Vec<u8>, Vec<(usize, Anchor)>, GraphicsFilter) {
+        let (proxy, _) = event_channel();
+        let store = Arc::new(Mutex::new(GraphicsStore::default()));
+        let mut filter = GraphicsFilter::new(store, proxy);
+        filter.cell_height = 4;
+        let mut output = Vec::new();
+        let mut anchors = Vec::new();
+        for mut chunk in input.iter().copied() {
+            while !chunk.is_empty() {
+                let len = filter.process(chunk, &mut output);
+                chunk = &chunk[len..];
+                if let Some(anchor) = filter.anchor.take() {
+                    anchors.push((output.len(), anchor));
+                }
+            }
+        }
+        (output, anchors, filter)
+    }
+
+    /// The PTY that returns the chunks and blocks after them
+    struct TestPty {
+        chunks: VecDeque<Vec<u8>>,
+        sink: io::Sink,
+    }
+
+    impl Read for TestPty {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some(chunk) = self.chunks.pop_front() else {
+                return Err(io::ErrorKind::WouldBlock.into());
+            };
+            buf[..chunk.len()].copy_from_slice(&chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    impl EventedReadWrite for TestPty {
+        type Reader = Self;
+        type Writer = io::Sink;
+
+        unsafe fn register(
+            &mut self,
+            _: &Arc<Poller>,
+            _: PollingEvent,
+            _: PollMode,
+        ) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn reregister(
+            &mut self,
+            _: &Arc<Poller>,
+            _: PollingEvent,
+            _: PollMode,
+        ) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn deregister(&mut self, _: &Arc<Poller>) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn reader(&mut self) -> &mut Self::Reader {
+            self
+        }
+
+        fn writer(&mut self) -> &mut Self::Writer {
+            &mut self.sink
+        }
+    }
+
+    /// Passes the chunks through the graphics PTY to the terminal,
+    /// the PTY is read like the event loop does until it's blocked
+    fn run_pty(
+        pty: &mut GraphicsPty<TestPty>,
+        parser: &mut Processor<StdSyncHandler>,
+        chunks: &[&[u8]],
+    ) {
+        pty.pty
+            .chunks
+            .extend(chunks.iter().map(|chunk| chunk.to_vec()));
+        let term = pty.term.clone();
+        let mut buf = [0; 256];
+        while !pty.pty.chunks.is_empty()
+            || !pty.pending.is_empty()
+            || pty.pause != Pause::None
+        {
+            // The terminal is locked until the read is blocked
+            let mut terminal = None;
+            while let Ok(len) = pty.read(&mut buf) {
+                let terminal =
+                    terminal.get_or_insert_with(|| term.lock_unfair());
+                parser.advance(&mut **terminal, &buf[..len]);
+            }
+        }
+    }
+
+    /// Returns the image rows shown on the terminal
+    fn shown_rows(pty: &GraphicsPty<TestPty>) -> Vec<(usize, usize, usize)> {
+        let mut grid = GridSnapshot::default();
+        grid.update(pty.term.lock().grid(), &Damage::Full);
+        let store = pty.filter.store.lock().unwrap();
+        let mut placements = Vec::new();
+        image_placements(&grid, &store, &mut placements);
+        placements
+            .iter()
+            .map(|placement| (placement.row, placement.line, placement.column))
+            .collect()
+    }
+
+    #[test]
+    fn pass_output_through() {
+        let input: &[&[u8]] = &[
+            b"text \x1b[1;31mred\x1b[0m \x1b]0;title\x07",
+            b"\x1b]8;;http://a\x1b\\link\x1bP+q544e\x1b\\\x1b7\x1b[>c",
+        ];
+        let (output, anchors, _) = filter(input);
+        assert_eq!(output, input.concat());
+        assert!(anchors.is_empty());
+    }
+
+    #[cfg(feature = "sixel")]
+    #[test]
+    fn pause_output_at_sixel() {
+        // The 1x6 image takes two rows of 4px cells, the second image
+        // is ended by the next sequence
+        let (output, anchors, filter) = filter(&[
+            b"a\x1bP0;1q#1;2;100;0;0",
+            b"~\x1b",
+            b"\\b\x1bPq~\x1b[Hc",
+        ]);
+        assert_eq!(output, b"ab\x1b[Hc");
+        let anchor = |placement_id| Anchor {
+            placement_id,
+            cursor: CursorMovement::NextLine,
+        };
+        assert_eq!(anchors, [(1, anchor(0)), (2, anchor(1))]);
+
+        let store = filter.store.lock().unwrap();
+        let image = store.image(0).unwrap();
+        assert_eq!((image.width, image.height), (1.0, 6.0));
+        assert_eq!(store.placement(0).unwrap().cell_height, 4.0);
+    }
+
+    fn graphics_pty() -> GraphicsPty<TestPty> {
+        let (proxy, _) = event_channel();
+        let term =
+            Term::new(Config::default(), &TermSize::new(10, 5), proxy.clone());
+        let store = Arc::new(Mutex::new(GraphicsStore::default()));
+        let test_pty = TestPty {
+            chunks: VecDeque::new(),
+            sink: io::sink(),
+        };
+        let term = Arc::new(FairMutex::new(term));
+        let mut pty = GraphicsPty::new(test_pty, store, term, proxy);
+        pty.filter.cell_width = 2;
+        pty.filter.cell_height = 4;
+        pty
+    }
+
+    fn line_text(pty: &GraphicsPty<TestPty>, line: i32) -> String {
+        let term = pty.term.lock();
+        let cells = &term.grid()[Line(line)];
+        (0..term.columns())
+            .map(|column| cells[Column(column)].c)
+            .collect()
+    }
+
+    #[cfg(feature = "sixel")]
+    #[test]
+    fn mark_sixel_cells() {
+        let mut pty = graphics_pty();
+        let mut parser = Processor::new();
+
+        // The 6x6 image takes three columns and two rows of 2x4px cells,
+        // the text under it is kept
+        let sixel: &[u8] = b"\x1bPq#1;2;100;0;0~~~~~~\x1b\\";
+        run_pty(
+            &mut pty,
+            &mut parser,
+            &[b"ab\x1b[1;4Hxy\x1b[1;3H", sixel, b"c"],
+        );
+        assert_eq!(shown_rows(&pty), [(0, 0, 2), (1, 1, 2)]);
+        assert_eq!(line_text(&pty, 0), "ab xy     ");
+        // The cursor is left below the image
+        assert_eq!(line_text(&pty, 2), "  c       ");
+
+        // The row is removed once its middle cell is overwritten
+        run_pty(&mut pty, &mut parser, &[b"\x1b[1;4Hz"]);
+        assert_eq!(shown_rows(&pty), [(1, 1, 2)]);
+    }
+
+    #[cfg(feature = "kitty-graphics")]
+    #[test]
+    fn keep_text_under_kitty_placements() {
+        let mut pty = graphics_pty();
+        let mut parser = Processor::new();
+
+        // The 4x8 image takes two columns and two rows of 2x4px cells,
+        // the text is written over it without moving the cursor
+        let payload = BASE64.encode([u8::MAX; 4 * 8 * 4]);
+        let kitty = format!("\x1b_Ga=T,s=4,v=8,i=1,C=1,z=-1;{}\x1b\\", payload);
+        run_pty(
+            &mut pty,
+            &mut parser,
+            &[b"abcdef\x1b[1;2H", kitty.as_bytes(), b"XY"],
+        );
+        assert_eq!(line_text(&pty, 0), "aXYdef    ");
+        assert_eq!(shown_rows(&pty), [(0, 0, 1), (1, 1, 1)]);
+
+        // The screen is not scrolled for the rows below it
+        let place = b"\x1b[5;1H\x1b_Ga=p,i=1,C=1\x1b\\";
+        run_pty(&mut pty, &mut parser, &[place]);
+        assert_eq!(line_text(&pty, 0), "aXYdef    ");
+        assert_eq!(shown_rows(&pty), [(0, 0, 1), (1, 1, 1), (0, 4, 0)]);
+
+        // The placements are removed once all of their cells are cleared
+        run_pty(&mut pty, &mut parser, &[b"\x1b[2J"]);
+        assert!(shown_rows(&pty).is_empty());
+    }
+
+    #[cfg(feature = "kitty-graphics")]
+    #[tokio::test]
+    async fn run_kitty_commands() {
+        use crate::backend::BackendEvent;
+
+        let (proxy, mut receiver) = event_channel();
+        let store = Arc::new(Mutex::new(GraphicsStore::default()));
+        let mut filter = GraphicsFilter::new(store, proxy);
+        let mut output = Vec::new();
+        // The other APC strings are passed through
+        filter.process(b"a\x1b_Ga=q,i=1,s=1,v=1;", &mut output);
+        filter.process(b"AAAAAA==\x1b\\\x1b_other\x1b\\b", &mut output);
+        assert_eq!(output, b"a\x1b_other\x1b\\b");
+
+        let Some(BackendEvent::Terminal(Event::PtyWrite(reply))) =
+            receiver.recv().await
+        else {
+            panic!("kitty graphics query is not replied");
+        };
+        assert_eq!(reply, "\x1b_Gi=1;OK\x1b\\");
+    }
+
+    #[cfg(feature = "kitty-graphics")]
+    #[tokio::test]
+    async fn wake_up_after_kitty_deletion() {
+        use crate::backend::BackendEvent;
+
+        let (proxy, mut receiver) = event_channel();
+        let store = Arc::new(Mutex::new(GraphicsStore::default()));
+        let mut filter = GraphicsFilter::new(store.clone(), proxy);
+        let mut output = Vec::new();
+        filter.process(b"\x1b_Ga=T,s=1,v=1,C=1;AAAAAA==\x1b\\", &mut output);
+        assert!(output.is_empty());
+        assert!(filter.anchor.take().is_some());
+        assert!(matches!(
+            receiver.recv().await,
+            Some(BackendEvent::Terminal(Event::Wakeup))
+        ));
+
+        // The deletion has no output, only the wakeup redraws the terminal
+        output.clear();
+        filter.process(b"\x1b_Ga=d,d=a\x1b\\", &mut output);
+        assert!(output.is_empty());
+        assert!(matches!(
+            receiver.recv().await,
+            Some(BackendEvent::Terminal(Event::Wakeup))
+        ));
+        assert_eq!(store.lock().unwrap().placements.len(), 0);
+    }
+
+    #[test]
+    fn clip_placement_rows() {
+        let image = StoredImage {
+            handle: Handle::from_rgba(8, 8, vec![0; 8 * 8 * 4]),
+            width: 8.0,
+            height: 8.0,
+            size: 8 * 8 * 4,
+        };
+        // The bottom half of the image was shown in 4px cells
+        let placement = Placement {
+            image_id: 0,
+            source: Rectangle {
+                x: 0.0,
+                y: 4.0,
+                width: 8.0,
+                height: 4.0,
+            },
+            bounds: Rectangle {
+                x: 1.0,
+                y: 2.0,
+                width: 8.0,
+                height: 4.0,
+            },
+            cell_height: 4.0,
+            z_index: 0,
+            columns: 5,
+            is_text: true,
+        };
+        assert_eq!(placement.rows(), 2);
+
+        // The font is twice as big now
+        let row = ImagePlacement {
+            placement_id: 0,
+            row: 1,
+            line: 3,
+            column: 2,
+        };
+        let viewport =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(100.0, 100.0));
+        let (image_bounds, clip_bounds) = placement
+            .row_bounds(&image, &row, viewport, Size::new(4.0, 8.0))
+            .unwrap();
+        assert_eq!(
+            image_bounds,
+            Rectangle::new(Point::new(20.0, 22.0), Size::new(16.0, 16.0))
+        );
+        assert_eq!(
+            clip_bounds,
+            Rectangle::new(Point::new(20.0, 34.0), Size::new(16.0, 4.0))
+        );
+    }
+
+    #[test]
+    fn parse_marker_uri() {
+        let store = GraphicsStore::default();
+        let uri = store.marker_uri(3, 7, 2);
+        assert_eq!(store.parse_marker(&uri), Some((3, 7, 2)));
+        assert_eq!(store.parse_marker(&format!("{}/1", uri)), None);
+        assert_eq!(store.parse_marker("https://example.com"), None);
+        assert_eq!(store.parse_marker("iced-term-image:3"), None);
+
+        // The markers of the other terminals are not accepted
+        let other = GraphicsStore::default();
+        assert_eq!(store.parse_marker(&other.marker_uri(3, 7, 2)), None);
+    }
+
+    #[test]
+    fn ignore_forged_markers() {
+        let (proxy, _) = event_channel();
+        let mut store = GraphicsStore::default();
+        let image_id = store.insert_image(1, 1, vec![0; 4]);
+        let placement_id = store.insert_placement(Placement {
+            image_id,
+            source: Rectangle::new(Point::ORIGIN, Size::new(1.0, 1.0)),
+            bounds: Rectangle::new(Point::ORIGIN, Size::new(1.0, 1.0)),
+            cell_height: 4.0,
+            z_index: 0,
+            columns: 1,
+            is_text: false,
+        });
+
+        // The application prints the hyperlinks of the guessed markers
+        let mut term =
+            Term::new(Config::default(), &TermSize::new(8, 4), proxy);
+        let mut parser = Processor::<StdSyncHandler>::new();
+        for uri in [
+            format!("{}{}/0/0", MARKER_SCHEME, placement_id),
+            format!("{}0/{}/0/0", MARKER_SCHEME, placement_id),
+        ] {
+            let link = format!("\x1b]8;;{}\x1b\\ \x1b]8;;\x1b\\\r\n", uri);
+            parser.advance(&mut term, link.as_bytes());
+        }
+        let mut grid = GridSnapshot::default();
+        grid.update(term.grid(), &Damage::Full);
+        let mut placements = Vec::new();
+        image_placements(&grid, &store, &mut placements);
+        assert!(placements.is_empty());
+
+        let link = format!(
+            "\x1b]8;;{}\x1b\\ \x1b]8;;\x1b\\",
+            store.marker_uri(placement_id, 0, 0)
+        );
+        parser.advance(&mut term, link.as_bytes());
+        grid.update(term.grid(), &Damage::Full);
+        image_placements(&grid, &store, &mut placements);
+        assert_eq!(placements.len(), 1);
+    }
+}
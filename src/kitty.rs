@@ -0,0 +1,739 @@
+use crate::graphics::{Anchor, CursorMovement, GraphicsStore, Placement};
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use iced::{Rectangle, Size};
+use std::collections::HashMap;
+use std::io::Cursor;
+
+/// The decoded images larger than this are rejected
+const MAX_IMAGE_BYTES: usize = 64 * 1024 * 1024;
+/// The longest command with its base64 payload, the chunks
+/// of the transmission are limited by it too
+pub(crate) const MAX_COMMAND_LEN: usize = MAX_IMAGE_BYTES / 3 * 4 + 4096;
+
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new()
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+type CommandResult = Result<(), &'static str>;
+
+/// The control data of the `ESC _ G <control data> ; <payload> ESC \`
+/// sequence, the missing keys have their default values
+#[derive(Debug, Clone, PartialEq)]
+struct Command {
+    action: u8,
+    quiet: u32,
+    format: u32,
+    medium: u8,
+    compression: u8,
+    more: bool,
+    image_id: u32,
+    image_number: u32,
+    placement_id: u32,
+    width: u32,
+    height: u32,
+    source: [u32; 4],
+    offset: [u32; 2],
+    columns: u32,
+    rows: u32,
+    z_index: i32,
+    keep_cursor: bool,
+    virtual_placement: bool,
+    delete: u8,
+}
+
+impl Default for Command {
+    fn default() -> Self {
+        Self {
+            action: b't',
+            quiet: 0,
+            format: 32,
+            medium: b'd',
+            compression: 0,
+            more: false,
+            image_id: 0,
+            image_number: 0,
+            placement_id: 0,
+            width: 0,
+            height: 0,
+            source: [0; 4],
+            offset: [0; 2],
+            columns: 0,
+            rows: 0,
+            z_index: 0,
+            keep_cursor: false,
+            virtual_placement: false,
+            delete: b'a',
+        }
+    }
+}
+
+impl Command {
+    /// Parses the comma separated `key=value` pairs,
+    /// the unknown and malformed ones are skipped
+    fn parse(control: &[u8]) -> Self {
+        let mut command = Self::default();
+        for pair in control.split(|&byte| byte == b',') {
+            let [key, b'=', value @ ..] = pair else {
+                continue;
+            };
+            let Some(&first) = value.first() else {
+                continue;
+            };
+            let number = std::str::from_utf8(value)
+                .ok()
+                .and_then(|value| value.parse::<i64>().ok())
+                .unwrap_or(0);
+            let unsigned = number.clamp(0, u32::MAX as i64) as u32;
+            match key {
+                b'a' => command.action = first,
+                b'q' => command.quiet = unsigned,
+                b'f' => command.format = unsigned,
+                b't' => command.medium = first,
+                b'o' => command.compression = first,
+                b'm' => command.more = unsigned == 1,
+                b'i' => command.image_id = unsigned,
+                b'I' => command.image_number = unsigned,
+                b'p' => command.placement_id = unsigned,
+                b's' => command.width = unsigned,
+                b'v' => command.height = unsigned,
+                b'x' => command.source[0] = unsigned,
+                b'y' => command.source[1] = unsigned,
+                b'w' => command.source[2] = unsigned,
+                b'h' => command.source[3] = unsigned,
+                b'X' => command.offset[0] = unsigned,
+                b'Y' => command.offset[1] = unsigned,
+                b'c' => command.columns = unsigned,
+                b'r' => command.rows = unsigned,
+                b'z' => {
+                    command.z_index =
+                        number.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+                },
+                b'C' => command.keep_cursor = unsigned == 1,
+                b'U' => command.virtual_placement = unsigned == 1,
+                b'd' => command.delete = first,
+                _ => {},
+            }
+        }
+
+        command
+    }
+
+    /// The reply is sent only to the commands with the image id or number
+    fn reply(&self, result: CommandResult) -> Option<String> {
+        if self.image_id == 0 && self.image_number == 0 {
+            return None;
+        }
+
+        let message = match result {
+            Ok(()) if self.quiet == 0 => "OK",
+            Err(error) if self.quiet < 2 => error,
+            _ => return None,
+        };
+        let mut reply = format!("\x1b_Gi={}", self.image_id);
+        if self.image_number != 0 {
+            reply.push_str(&format!(",I={}", self.image_number));
+        }
+        if self.placement_id != 0 {
+            reply.push_str(&format!(",p={}", self.placement_id));
+        }
+        reply.push_str(&format!(";{}\x1b\\", message));
+        Some(reply)
+    }
+}
+
+/// The image transmitted in several chunks
+#[derive(Debug)]
+struct Transfer {
+    command: Command,
+    payload: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KittyPlacement {
+    /// The image id given by the application, 0 if there is none
+    image_id: u32,
+    placement_id: u32,
+    z_index: i32,
+    /// The ids in the graphics store
+    image: u32,
+    placement: u32,
+}
+
+/// The state of the kitty graphics protocol,
+/// the application ids are mapped to the graphics store ones
+#[derive(Debug, Default)]
+pub(crate) struct KittyGraphics {
+    images: HashMap<u32, u32>,
+    numbers: HashMap<u32, u32>,
+    placements: Vec<KittyPlacement>,
+    transfer: Option<Transfer>,
+    /// The chunks of the too big transfer are skipped until its last one
+    discarding: bool,
+    next_image_id: u32,
+}
+
+impl KittyGraphics {
+    /// Runs the command from the data of the `ESC _ G ... ESC \` sequence,
+    /// the placement is set to `anchor` and the reply is returned
+    pub(crate) fn process(
+        &mut self,
+        data: &[u8],
+        cell: Size,
+        store: &mut GraphicsStore,
+        anchor: &mut Option<Anchor>,
+    ) -> Option<String> {
+        let (control, payload) = match data.iter().position(|&b| b == b';') {
+            Some(index) => (&data[..index], &data[index + 1..]),
+            None => (data, &data[data.len()..]),
+        };
+        let command = Command::parse(control);
+
+        // The placements of the evicted images are gone from the store
+        self.placements
+            .retain(|placement| store.placement(placement.placement).is_some());
+
+        if self.discarding {
+            self.discarding = command.more;
+            return None;
+        }
+
+        // The next chunks only have the `m` key
+        if let Some(mut transfer) = self.transfer.take() {
+            if transfer.payload.len() + payload.len() > MAX_COMMAND_LEN {
+                self.discarding = command.more;
+                return transfer.command.reply(Err("EFBIG:image is too big"));
+            }
+
+            transfer.payload.extend_from_slice(payload);
+            if command.more {
+                self.transfer = Some(transfer);
+                return None;
+            }
+
+            return self.run(
+                transfer.command,
+                &transfer.payload,
+                cell,
+                store,
+                anchor,
+            );
+        }
+
+        if command.more && matches!(command.action, b't' | b'T' | b'q') {
+            self.transfer = Some(Transfer {
+                command,
+                payload: payload.to_vec(),
+            });
+            return None;
+        }
+
+        self.run(command, payload, cell, store, anchor)
+    }
+
+    fn run(
+        &mut self,
+        mut command: Command,
+        payload: &[u8],
+        cell: Size,
+        store: &mut GraphicsStore,
+        anchor: &mut Option<Anchor>,
+    ) -> Option<String> {
+        let result = match command.action {
+            b't' | b'T' => {
+                self.transmit(&mut command, payload, cell, store, anchor)
+            },
+            b'q' => decode_image(&command, payload).map(|_| ()),
+            b'p' => self.place(&mut command, cell, store, anchor),
+            b'd' => {
+                self.delete(&command, store);
+                return None;
+            },
+            _ => Err("EINVAL:unsupported action"),
+        };
+
+        command.reply(result)
+    }
+
+    fn transmit(
+        &mut self,
+        command: &mut Command,
+        payload: &[u8],
+        cell: Size,
+        store: &mut GraphicsStore,
+        anchor: &mut Option<Anchor>,
+    ) -> CommandResult {
+        let (width, height, pixels) = decode_image(command, payload)?;
+        let image = store.insert_image(width, height, pixels);
+
+        // The image number gets a new id that is sent in the reply
+        if command.image_id == 0 && command.image_number != 0 {
+            command.image_id = self.next_image_id();
+        }
+        if command.image_id != 0 {
+            if let Some(replaced) = self.images.insert(command.image_id, image)
+            {
+                store.remove_image(replaced);
+            }
+        }
+        if command.image_number != 0 {
+            self.numbers.insert(command.image_number, command.image_id);
+        }
+
+        if command.action == b'T' {
+            self.place_image(command, image, cell, store, anchor)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn place(
+        &mut self,
+        command: &mut Command,
+        cell: Size,
+        store: &mut GraphicsStore,
+        anchor: &mut Option<Anchor>,
+    ) -> CommandResult {
+        if command.image_id == 0 {
+            command.image_id = self
+                .numbers
+                .get(&command.image_number)
+                .copied()
+                .unwrap_or(0);
+        }
+
+        let image = self
+            .images
+            .get(&command.image_id)
+            .copied()
+            .ok_or("ENOENT:image not found")?;
+        self.place_image(command, image, cell, store, anchor)
+    }
+
+    /// Attaches the image to the cells from the cursor
+    fn place_image(
+        &mut self,
+        command: &Command,
+        image: u32,
+        cell: Size,
+        store: &mut GraphicsStore,
+        anchor: &mut Option<Anchor>,
+    ) -> CommandResult {
+        if command.virtual_placement {
+            return Err("EINVAL:unicode placeholders are not supported");
+        }
+
+        // The zero source width or height shows the rest of the image
+        let stored = store.image(image).ok_or("ENOENT:image not found")?;
+        let [x, y, width, height] = command.source.map(|value| value as f32);
+        let x = x.min(stored.width);
+        let y = y.min(stored.height);
+        let width = if width > 0.0 { width } else { stored.width };
+        let height = if height > 0.0 { height } else { stored.height };
+        let width = width.min(stored.width - x);
+        let height = height.min(stored.height - y);
+        if width <= 0.0 || height <= 0.0 {
+            return Err("EINVAL:source rectangle is empty");
+        }
+
+        // The missing columns or rows keep the aspect ratio
+        let size = match (command.columns, command.rows) {
+            (0, 0) => Size::new(width, height),
+            (columns, 0) => {
+                let shown_width = columns as f32 * cell.width;
+                Size::new(shown_width, shown_width * height / width)
+            },
+            (0, rows) => {
+                let shown_height = rows as f32 * cell.height;
+                Size::new(shown_height * width / height, shown_height)
+            },
+            (columns, rows) => Size::new(
+                columns as f32 * cell.width,
+                rows as f32 * cell.height,
+            ),
+        };
+        let [offset_x, offset_y] = command.offset.map(|value| value as f32);
+        let placement = Placement {
+            image_id: image,
+            source: Rectangle {
+                x,
+                y,
+                width,
+                height,
+            },
+            bounds: Rectangle {
+                x: offset_x,
+                y: offset_y,
+                width: size.width,
+                height: size.height,
+            },
+            cell_height: cell.height,
+            z_index: command.z_index,
+            columns: ((offset_x + size.width) / cell.width).ceil() as usize,
+            is_text: false,
+        };
+
+        // The placement with the same id is moved
+        if command.placement_id != 0 {
+            self.remove_placements(store, |placement| {
+                placement.image_id == command.image_id
+                    && placement.placement_id == command.placement_id
+            });
+        }
+
+        let placement_id = store.insert_placement(placement);
+        self.placements.push(KittyPlacement {
+            image_id: command.image_id,
+            placement_id: command.placement_id,
+            z_index: command.z_index,
+            image,
+            placement: placement_id,
+        });
+
+        let cursor = if command.keep_cursor {
+            CursorMovement::None
+        } else {
+            CursorMovement::AfterPlacement(placement.columns)
+        };
+        *anchor = Some(Anchor {
+            placement_id,
+            cursor,
+        });
+        Ok(())
+    }
+
+    /// The uppercase deletions free the images too,
+    /// the ones that need the cursor or cell positions are not supported
+    fn delete(&mut self, command: &Command, store: &mut GraphicsStore) {
+        let image_id = match command.delete.to_ascii_lowercase() {
+            b'n' => self.numbers.get(&command.image_number).copied(),
+            _ => Some(command.image_id),
+        };
+        let removed = match (command.delete.to_ascii_lowercase(), image_id) {
+            (b'a', _) => self.remove_placements(store, |_| true),
+            (b'i' | b'n', Some(image_id)) if image_id != 0 => self
+                .remove_placements(store, |placement| {
+                    placement.image_id == image_id
+                        && (command.placement_id == 0
+                            || placement.placement_id == command.placement_id)
+                }),
+            (b'z', _) => self.remove_placements(store, |placement| {
+                placement.z_index == command.z_index
+            }),
+            _ => return,
+        };
+        if !command.delete.is_ascii_uppercase() {
+            return;
+        }
+
+        let mut images = removed;
+        if let Some(image) = image_id.and_then(|id| self.images.get(&id)) {
+            images.push(*image);
+        }
+        for image in images {
+            if self.placements.iter().all(|p| p.image != image) {
+                self.free_image(store, image);
+            }
+        }
+    }
+
+    /// Returns the store images of the removed placements
+    fn remove_placements(
+        &mut self,
+        store: &mut GraphicsStore,
+        predicate: impl Fn(&KittyPlacement) -> bool,
+    ) -> Vec<u32> {
+        let mut images = Vec::new();
+        self.placements.retain(|placement| {
+            if !predicate(placement) {
+                return true;
+            }
+
+            store.remove_placement(placement.placement);
+            images.push(placement.image);
+            false
+        });
+        images
+    }
+
+    fn free_image(&mut self, store: &mut GraphicsStore, image: u32) {
+        store.remove_image(image);
+        let ids: Vec<u32> = self
+            .images
+            .iter()
+            .filter(|(_, &stored)| stored == image)
+            .map(|(&id, _)| id)
+            .collect();
+        for id in ids {
+            self.images.remove(&id);
+            self.numbers.retain(|_, &mut numbered| numbered != id);
+        }
+    }
+
+    fn next_image_id(&mut self) -> u32 {
+        loop {
+            self.next_image_id = self.next_image_id.wrapping_add(1).max(1);
+            if !self.images.contains_key(&self.next_image_id) {
+                return self.next_image_id;
+            }
+        }
+    }
+}
+
+/// Returns the width, height and RGBA pixels of the transmitted image
+fn decode_image(
+    command: &Command,
+    payload: &[u8],
+) -> Result<(u32, u32, Vec<u8>), &'static str> {
+    if command.medium != b'd' {
+        return Err("EINVAL:only the direct transmission is supported");
+    }
+
+    let data = BASE64
+        .decode(payload)
+        .map_err(|_| "EINVAL:invalid base64 data")?;
+    let data = match command.compression {
+        0 => data,
+        b'z' => miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(
+            &data,
+            MAX_IMAGE_BYTES,
+        )
+        .map_err(|_| "EINVAL:invalid compressed data")?,
+        _ => return Err("EINVAL:unsupported compression"),
+    };
+
+    match command.format {
+        24 | 32 => {
+            let (width, height) = (command.width, command.height);
+            if width == 0 || height == 0 {
+                return Err("EINVAL:image size is not specified");
+            }
+            let pixels = width as usize * height as usize;
+            if pixels > MAX_IMAGE_BYTES / 4 {
+                return Err("EFBIG:image is too big");
+            }
+
+            let channels = command.format as usize / 8;
+            let data = data
+                .get(..pixels * channels)
+                .ok_or("ENODATA:insufficient image data")?;
+            let pixels = if channels == 4 {
+                data.to_vec()
+            } else {
+                data.chunks_exact(3)
+                    .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], u8::MAX])
+                    .collect()
+            };
+            Ok((width, height, pixels))
+        },
+        100 => decode_png(&data),
+        _ => Err("EINVAL:unsupported format"),
+    }
+}
+
+fn decode_png(data: &[u8]) -> Result<(u32, u32, Vec<u8>), &'static str> {
+    const INVALID_PNG: &str = "EBADPNG:invalid PNG data";
+
+    let mut decoder = png::Decoder::new_with_limits(
+        Cursor::new(data),
+        png::Limits {
+            bytes: MAX_IMAGE_BYTES,
+        },
+    );
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|_| INVALID_PNG)?;
+    let size = reader
+        .output_buffer_size()
+        .ok_or("EFBIG:image is too big")?;
+    let mut buffer = vec![0; size];
+    let info = reader.next_frame(&mut buffer).map_err(|_| INVALID_PNG)?;
+    buffer.truncate(info.buffer_size());
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => buffer
+            .chunks_exact(3)
+            .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], u8::MAX])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buffer
+            .chunks_exact(2)
+            .flat_map(|gray| [gray[0], gray[0], gray[0], gray[1]])
+            .collect(),
+        png::ColorType::Grayscale => buffer
+            .iter()
+            .flat_map(|&gray| [gray, gray, gray, u8::MAX])
+            .collect(),
+        png::ColorType::Indexed => return Err(INVALID_PNG),
+    };
+    Ok((info.width, info.height, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CELL: Size = Size::new(2.0, 4.0);
+
+    fn run(
+        kitty: &mut KittyGraphics,
+        store: &mut GraphicsStore,
+        data: &str,
+    ) -> (Option<String>, Option<Anchor>) {
+        let mut anchor = None;
+        let reply = kitty.process(data.as_bytes(), CELL, store, &mut anchor);
+        (reply, anchor)
+    }
+
+    fn rgba(width: usize, height: usize) -> String {
+        BASE64.encode(vec![u8::MAX; width * height * 4])
+    }
+
+    #[test]
+    fn parse_command() {
+        let command = Command::parse(b"a=T,f=100,i=7,z=-5,C=1,bogus,k=1");
+        assert_eq!(command.action, b'T');
+        assert_eq!(command.format, 100);
+        assert_eq!(command.image_id, 7);
+        assert_eq!(command.z_index, -5);
+        assert!(command.keep_cursor);
+        assert_eq!(command.medium, b'd');
+    }
+
+    #[test]
+    fn transmit_and_reply() {
+        let mut kitty = KittyGraphics::default();
+        let mut store = GraphicsStore::default();
+
+        // The chunks are joined and replied once
+        let data = rgba(3, 2);
+        let (first, rest) = data.split_at(8);
+        let command = format!("a=t,s=3,v=2,i=5,m=1;{}", first);
+        assert_eq!(run(&mut kitty, &mut store, &command).0, None);
+        let (reply, anchor) =
+            run(&mut kitty, &mut store, &format!("m=0;{}", rest));
+        assert_eq!(reply.as_deref(), Some("\x1b_Gi=5;OK\x1b\\"));
+        assert!(anchor.is_none());
+
+        let image = store.image(kitty.images[&5]).unwrap();
+        assert_eq!((image.width, image.height), (3.0, 2.0));
+
+        // The image number gets the new id
+        let command = format!("s=1,v=1,I=9;{}", rgba(1, 1));
+        let (reply, _) = run(&mut kitty, &mut store, &command);
+        assert_eq!(reply.as_deref(), Some("\x1b_Gi=1,I=9;OK\x1b\\"));
+
+        // The quiet commands and the ones without the id are not replied
+        let command = format!("s=1,v=1,i=6,q=1;{}", rgba(1, 1));
+        assert_eq!(run(&mut kitty, &mut store, &command).0, None);
+        let command = format!("s=1,v=1;{}", rgba(1, 1));
+        assert_eq!(run(&mut kitty, &mut store, &command).0, None);
+    }
+
+    #[test]
+    fn report_errors() {
+        let mut kitty = KittyGraphics::default();
+        let mut store = GraphicsStore::default();
+
+        let (reply, _) = run(&mut kitty, &mut store, "a=p,i=3");
+        assert_eq!(
+            reply.as_deref(),
+            Some("\x1b_Gi=3;ENOENT:image not found\x1b\\")
+        );
+        let (reply, _) = run(&mut kitty, &mut store, "a=q,i=4,s=2,v=2;AAAA");
+        assert_eq!(
+            reply.as_deref(),
+            Some("\x1b_Gi=4;ENODATA:insufficient image data\x1b\\")
+        );
+        let (reply, _) = run(&mut kitty, &mut store, "a=q,i=4,t=f;AAAA");
+        assert!(reply.unwrap().contains("EINVAL"));
+        assert_eq!(run(&mut kitty, &mut store, "a=p,i=3,q=2").0, None);
+    }
+
+    #[test]
+    fn discard_too_big_transfer() {
+        let mut kitty = KittyGraphics::default();
+        let mut store = GraphicsStore::default();
+
+        let chunk = "A".repeat(MAX_COMMAND_LEN / 2 + 4);
+        let command = format!("a=T,s=1,v=1,i=2,m=1;{}", chunk);
+        assert_eq!(run(&mut kitty, &mut store, &command).0, None);
+        let (reply, _) =
+            run(&mut kitty, &mut store, &format!("m=1;{}{}", chunk, chunk));
+        assert_eq!(
+            reply.as_deref(),
+            Some("\x1b_Gi=2;EFBIG:image is too big\x1b\\")
+        );
+
+        // The rest of the transfer is skipped without the replies
+        for data in ["m=1;AAAA", "m=1;AAAA", "m=0;AAAA"] {
+            let (reply, anchor) = run(&mut kitty, &mut store, data);
+            assert_eq!(reply, None);
+            assert!(anchor.is_none());
+        }
+        assert!(kitty.transfer.is_none());
+        assert!(kitty.images.is_empty());
+
+        // The next command is run again
+        let command = format!("a=T,s=1,v=1,i=3,C=1;{}", rgba(1, 1));
+        let (reply, _) = run(&mut kitty, &mut store, &command);
+        assert_eq!(reply.as_deref(), Some("\x1b_Gi=3;OK\x1b\\"));
+    }
+
+    #[test]
+    fn decode_png_payload() {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, 2, 1);
+        encoder.set_color(png::ColorType::Rgb);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[255, 0, 0, 0, 0, 255]).unwrap();
+        writer.finish().unwrap();
+
+        let command = Command::parse(b"f=100");
+        let (width, height, pixels) =
+            decode_image(&command, BASE64.encode(&png).as_bytes()).unwrap();
+        assert_eq!((width, height), (2, 1));
+        assert_eq!(pixels, [255, 0, 0, 255, 0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn place_and_delete() {
+        let mut kitty = KittyGraphics::default();
+        let mut store = GraphicsStore::default();
+        let command = format!("a=T,s=3,v=6,i=1,z=-1,q=2;{}", rgba(3, 6));
+        let (_, anchor) = run(&mut kitty, &mut store, &command);
+
+        // Two rows of 4px cells, the cursor is moved after two columns
+        let Some(Anchor {
+            placement_id,
+            cursor,
+        }) = anchor
+        else {
+            panic!("image is not placed");
+        };
+        assert_eq!(cursor, CursorMovement::AfterPlacement(2));
+        let placement = *store.placement(placement_id).unwrap();
+        assert_eq!((placement.rows(), placement.columns), (2, 2));
+        assert_eq!(placement.z_index, -1);
+
+        // The same placement id moves the placement
+        run(&mut kitty, &mut store, "a=p,i=1,p=2,c=1,C=1");
+        run(&mut kitty, &mut store, "a=p,i=1,p=2,c=2,C=1");
+        assert_eq!(kitty.placements.len(), 2);
+        assert_eq!(kitty.placements[1].placement_id, 2);
+
+        run(&mut kitty, &mut store, "a=d,d=z,z=-1");
+        assert!(store.placement(placement_id).is_none());
+        assert_eq!(kitty.placements.len(), 1);
+
+        // The uppercase deletion frees the image
+        let image = kitty.images[&1];
+        run(&mut kitty, &mut store, "a=d,d=I,i=1");
+        assert!(kitty.placements.is_empty());
+        assert!(store.image(image).is_none());
+        assert!(kitty.images.is_empty());
+    }
+}
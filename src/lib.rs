@@ -3,7 +3,18 @@ pub mod bindings;
 pub mod settings;
 
 mod backend;
+mod box_drawing;
+#[cfg(feature = "color-schemes")]
+mod color_scheme;
+mod cursor;
+mod decoration;
 mod font;
+#[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+mod graphics;
+#[cfg(feature = "kitty-graphics")]
+mod kitty;
+#[cfg(feature = "sixel")]
+mod sixel;
 mod terminal;
 mod theme;
 mod view;
@@ -13,7 +24,9 @@ pub use alacritty_terminal::index::Point as AlacrittyPoint;
 pub use alacritty_terminal::selection::SelectionType;
 pub use alacritty_terminal::term::TermMode;
 pub use backend::Command as BackendCommand;
-pub use backend::{LinkAction, MouseButton};
+pub use backend::{BackendError, LinkAction, MouseButton};
+#[cfg(feature = "color-schemes")]
+pub use color_scheme::ColorSchemeError;
 pub use terminal::{Command, Event, Terminal};
-pub use theme::{ColorPalette, Theme};
+pub use theme::{ColorCube, ColorPalette, ColorParseError, Theme};
 pub use view::TerminalView;
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
 use crate::ColorPalette;
 use iced::Font;
@@ -14,6 +14,8 @@ pub struct Settings {
     pub font: FontSettings,
     pub theme: ThemeSettings,
     pub backend: BackendSettings,
+    pub keyboard: KeyboardSettings,
+    pub cursor: CursorSettings,
 }
 
 #[derive(Debug, Clone)]
@@ -35,11 +37,90 @@ impl Default for BackendSettings {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct KeyboardSettings {
+    /// Encode modified keys without a binding as xterm `modifyOtherKeys`
+    /// level 2 sequences (`CSI 27;<mod>;<code>~`). The terminal backend
+    /// does not track the mode requested by the application,
+    /// so it has to be enabled here explicitly.
+    pub modify_other_keys: bool,
+    /// Which Alt (Option on macOS) keys send `ESC` prefixed characters
+    /// instead of the composed text.
+    pub alt_as_meta: AltAsMeta,
+    /// How long the pressed prefix of a chord binding
+    /// waits for the next key.
+    pub chord_timeout: Duration,
+}
+
+impl Default for KeyboardSettings {
+    fn default() -> Self {
+        Self {
+            modify_other_keys: false,
+            alt_as_meta: AltAsMeta::default(),
+            chord_timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AltAsMeta {
+    #[default]
+    Disabled,
+    OnlyLeft,
+    OnlyRight,
+    Both,
+}
+
+#[derive(Debug, Clone)]
+pub struct CursorSettings {
+    /// The cursor style used until an application requests
+    /// another one with DECSCUSR
+    pub shape: CursorShape,
+    pub blinking: bool,
+    pub blink_interval: Duration,
+    /// Blinking stops after this long without input
+    pub blink_timeout: Duration,
+}
+
+impl Default for CursorSettings {
+    fn default() -> Self {
+        Self {
+            shape: CursorShape::default(),
+            blinking: false,
+            blink_interval: Duration::from_millis(750),
+            blink_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorShape {
+    #[default]
+    Block,
+    Underline,
+    Beam,
+}
+
 #[derive(Debug, Clone)]
 pub struct FontSettings {
     pub size: f32,
     pub scale_factor: f32,
+    /// The regular face, the cell size is measured with it
     pub font_type: Font,
+    /// The bold face, the regular one with the bold weight if it's not set
+    pub bold: Option<Font>,
+    /// The italic face, the regular one with the italic style
+    /// if it's not set
+    pub italic: Option<Font>,
+    /// The bold italic face, it's derived from the bold
+    /// or the italic one if it's not set
+    pub bold_italic: Option<Font>,
+    /// The fonts tried in order for the glyphs missing from the face,
+    /// like symbols or CJK
+    pub fallback: Vec<Font>,
+    /// Draw the box-drawing, block element and Powerline glyphs
+    /// with paths that fill the whole cell instead of the font
+    pub builtin_box_drawing: bool,
 }
 
 impl Default for FontSettings {
@@ -48,6 +129,11 @@ impl Default for FontSettings {
             size: 14.0,
             scale_factor: 1.3,
             font_type: Font::MONOSPACE,
+            bold: None,
+            italic: None,
+            bold_italic: None,
+            fallback: Vec::new(),
+            builtin_box_drawing: true,
         }
     }
 }
@@ -55,10 +141,16 @@ impl Default for FontSettings {
 #[derive(Default, Debug, Clone)]
 pub struct ThemeSettings {
     pub color_pallete: Box<ColorPalette>,
+    /// Bold text of the normal 0..=7 colors
+    /// is drawn with the bright colors
+    pub draw_bold_text_with_bright_colors: bool,
 }
 
 impl ThemeSettings {
     pub fn new(color_pallete: Box<ColorPalette>) -> Self {
-        Self { color_pallete }
+        Self {
+            color_pallete,
+            draw_bold_text_with_bright_colors: false,
+        }
     }
 }
@@ -0,0 +1,309 @@
+/// The images larger than this are cropped
+const MAX_IMAGE_SIZE: usize = 4096;
+const PALETTE_SIZE: usize = 256;
+
+/// The VT340 default color registers, in RGB percents
+const DEFAULT_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (20, 20, 80),
+    (80, 13, 13),
+    (20, 80, 20),
+    (80, 20, 80),
+    (20, 80, 80),
+    (80, 80, 20),
+    (53, 53, 53),
+    (26, 26, 26),
+    (33, 33, 60),
+    (60, 26, 26),
+    (33, 60, 33),
+    (60, 33, 60),
+    (33, 60, 60),
+    (60, 60, 33),
+    (80, 80, 80),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SixelImage {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    /// RGBA pixels, row by row
+    pub(crate) pixels: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Data,
+    Repeat,
+    Color,
+    Raster,
+}
+
+/// Decodes the data of the `DCS <params> q <data> ST` sequence
+pub(crate) struct SixelDecoder {
+    state: State,
+    params: Vec<u32>,
+    palette: [u32; PALETTE_SIZE],
+    color: u32,
+    is_transparent: bool,
+    x: usize,
+    y: usize,
+    repeat: usize,
+    width: usize,
+    height: usize,
+    /// The pixels of every row, 0 is an unset pixel
+    rows: Vec<Vec<u32>>,
+}
+
+impl SixelDecoder {
+    /// `params` are the DCS params, the second one selects
+    /// whether the unset pixels are transparent
+    pub(crate) fn new(params: &[u32]) -> Self {
+        let mut palette = [rgba(0, 0, 0); PALETTE_SIZE];
+        for (color, (r, g, b)) in palette.iter_mut().zip(DEFAULT_PALETTE) {
+            *color = rgb_percents(r as u32, g as u32, b as u32);
+        }
+
+        Self {
+            state: State::Data,
+            params: Vec::new(),
+            color: palette[0],
+            palette,
+            is_transparent: params.get(1) == Some(&1),
+            x: 0,
+            y: 0,
+            repeat: 1,
+            width: 0,
+            height: 0,
+            rows: Vec::new(),
+        }
+    }
+
+    pub(crate) fn put(&mut self, byte: u8) {
+        match self.state {
+            State::Repeat | State::Color | State::Raster
+                if byte.is_ascii_digit() || byte == b';' =>
+            {
+                self.put_param(byte);
+                return;
+            },
+            State::Repeat => {
+                self.repeat =
+                    self.params.first().copied().unwrap_or(1).max(1) as usize;
+            },
+            State::Color => self.select_color(),
+            State::Raster => self.set_raster_attributes(),
+            State::Data => {},
+        }
+
+        self.state = State::Data;
+        self.params.clear();
+        match byte {
+            b'?'..=b'~' => {
+                self.put_sixel(byte - b'?');
+                self.repeat = 1;
+            },
+            b'!' => self.state = State::Repeat,
+            b'#' => self.state = State::Color,
+            b'"' => self.state = State::Raster,
+            b'$' => self.x = 0,
+            b'-' => {
+                self.x = 0;
+                self.y += 6;
+            },
+            _ => {},
+        }
+    }
+
+    /// Returns `None` if there is nothing to draw
+    pub(crate) fn finish(mut self) -> Option<SixelImage> {
+        match self.state {
+            State::Color => self.select_color(),
+            State::Raster => self.set_raster_attributes(),
+            State::Data | State::Repeat => {},
+        }
+
+        let width = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let width = width.max(self.width);
+        let height = self.rows.len().max(self.height);
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        // Unless the image is transparent, the unset pixels
+        // are filled with the first color register
+        let background = if self.is_transparent {
+            0
+        } else {
+            self.palette[0]
+        };
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            let row = self.rows.get(y).map(Vec::as_slice).unwrap_or_default();
+            for x in 0..width {
+                let color = match row.get(x) {
+                    Some(&color) if color != 0 => color,
+                    _ => background,
+                };
+                pixels.extend_from_slice(&color.to_be_bytes());
+            }
+        }
+
+        Some(SixelImage {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    fn put_param(&mut self, byte: u8) {
+        if byte == b';' {
+            self.params.push(0);
+            return;
+        }
+
+        if self.params.is_empty() {
+            self.params.push(0);
+        }
+        if let Some(param) = self.params.last_mut() {
+            *param = param
+                .saturating_mul(10)
+                .saturating_add((byte - b'0') as u32);
+        }
+    }
+
+    fn select_color(&mut self) {
+        let index = self.params.first().copied().unwrap_or(0) as usize;
+        let index = index % PALETTE_SIZE;
+        if let [_, space, x, y, z] = self.params[..] {
+            match space {
+                1 => self.palette[index] = hls(x, y, z),
+                2 => self.palette[index] = rgb_percents(x, y, z),
+                _ => {},
+            }
+        }
+
+        self.color = self.palette[index];
+    }
+
+    fn set_raster_attributes(&mut self) {
+        if let [_, _, width, height] = self.params[..] {
+            self.width = (width as usize).min(MAX_IMAGE_SIZE);
+            self.height = (height as usize).min(MAX_IMAGE_SIZE);
+        }
+    }
+
+    fn put_sixel(&mut self, bits: u8) {
+        let start = self.x;
+        let end = (self.x + self.repeat).min(MAX_IMAGE_SIZE);
+        self.x += self.repeat;
+        if start >= end {
+            return;
+        }
+
+        for bit in 0..6 {
+            let y = self.y + bit;
+            if bits & (1 << bit) == 0 || y >= MAX_IMAGE_SIZE {
+                continue;
+            }
+
+            if self.rows.len() <= y {
+                self.rows.resize_with(y + 1, Vec::new);
+            }
+            let row = &mut self.rows[y];
+            if row.len() < end {
+                row.resize(end, 0);
+            }
+            row[start..end].fill(self.color);
+        }
+    }
+}
+
+fn rgba(r: u8, g: u8, b: u8) -> u32 {
+    u32::from_be_bytes([r, g, b, 255])
+}
+
+fn rgb_percents(r: u32, g: u32, b: u32) -> u32 {
+    let channel = |value: u32| (value.min(100) * 255 / 100) as u8;
+    rgba(channel(r), channel(g), channel(b))
+}
+
+/// The hue of the sixel HLS colors starts from blue
+fn hls(hue: u32, lightness: u32, saturation: u32) -> u32 {
+    let hue = ((hue + 240) % 360) as f32;
+    let lightness = lightness.min(100) as f32 / 100.0;
+    let saturation = saturation.min(100) as f32 / 100.0;
+
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = lightness - chroma / 2.0;
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |value: f32| ((value + m) * 255.0).round() as u8;
+
+    rgba(channel(r), channel(g), channel(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(params: &[u32], data: &str) -> Option<SixelImage> {
+        let mut decoder = SixelDecoder::new(params);
+        for byte in data.bytes() {
+            decoder.put(byte);
+        }
+        decoder.finish()
+    }
+
+    fn pixel(image: &SixelImage, x: usize, y: usize) -> [u8; 4] {
+        let start = (y * image.width + x) * 4;
+        image.pixels[start..start + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn decode_sixels() {
+        // Red 2x6 column, then the green pixel on the next band
+        let image = decode(&[0, 1], "#1;2;100;0;0#1!2~-#2;2;0;100;0@").unwrap();
+        assert_eq!((image.width, image.height), (2, 7));
+        assert_eq!(pixel(&image, 0, 0), [255, 0, 0, 255]);
+        assert_eq!(pixel(&image, 1, 5), [255, 0, 0, 255]);
+        assert_eq!(pixel(&image, 0, 6), [0, 255, 0, 255]);
+        // The image is transparent
+        assert_eq!(pixel(&image, 1, 6), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn decode_raster_attributes_and_background() {
+        let image = decode(&[], "\"1;1;3;2#0;2;0;0;100@").unwrap();
+        assert_eq!((image.width, image.height), (3, 2));
+        assert_eq!(pixel(&image, 0, 0), [0, 0, 255, 255]);
+        // The unset pixels are filled with the first color register
+        assert_eq!(pixel(&image, 2, 1), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn overwrite_with_carriage_return() {
+        let image = decode(&[0, 1], "#1;2;100;0;0@$#2;2;0;100;0@").unwrap();
+        assert_eq!((image.width, image.height), (1, 1));
+        assert_eq!(pixel(&image, 0, 0), [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn hls_colors() {
+        // The hue starts from blue, red is at 120 degrees
+        assert_eq!(hls(0, 50, 100), rgba(0, 0, 255));
+        assert_eq!(hls(120, 50, 100), rgba(255, 0, 0));
+        assert_eq!(hls(240, 50, 100), rgba(0, 255, 0));
+    }
+
+    #[test]
+    fn skip_empty_images() {
+        assert_eq!(decode(&[], "#1;2;100;0;0"), None);
+    }
+}
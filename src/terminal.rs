@@ -1,10 +1,17 @@
 use crate::actions::Action;
-use crate::backend;
-use crate::bindings::{Binding, BindingAction, BindingsLayout, InputKind};
+use crate::backend::{self, BackendError, BackendEvent, Damage, EventReceiver};
+use crate::bindings::{
+    Binding, BindingAction, BindingsLayout, InputKind, KeyboardChord,
+};
+use crate::cursor::CursorBlink;
 use crate::font::TermFont;
-use crate::settings::{FontSettings, Settings, ThemeSettings};
+use crate::settings::{
+    FontSettings, KeyboardSettings, Settings, ThemeSettings,
+};
 use crate::theme::{ColorPalette, Theme};
-use crate::AlacrittyEvent;
+use crate::{AlacrittyEvent, TermMode};
+use alacritty_terminal::grid::Dimensions;
+use iced::futures::channel::mpsc::{SendError, Sender};
 use iced::futures::stream::BoxStream;
 use iced::futures::{SinkExt, StreamExt};
 use iced::widget::canvas::Cache;
@@ -12,20 +19,58 @@ use iced::Subscription;
 use std::hash::{Hash, Hasher};
 use std::io::Result;
 use std::sync::Arc;
-use tokio::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub enum Event {
     BackendCall(u64, backend::Command),
+    BindingCall(u64, BindingAction),
+    CursorBlink(u64),
+    BackendFailed(u64, BackendError),
+}
+
+impl Event {
+    /// The id of the terminal that has produced the event
+    pub fn terminal_id(&self) -> u64 {
+        match self {
+            Event::BackendCall(id, _)
+            | Event::BindingCall(id, _)
+            | Event::CursorBlink(id)
+            | Event::BackendFailed(id, _) => *id,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum Command {
+    /// Invalid palette colors fall back to the default ones,
+    /// check them with [`ColorPalette::validate`] first
     ChangeTheme(Box<ColorPalette>),
     ChangeFont(FontSettings),
     AddBindings(Vec<(Binding<InputKind>, BindingAction)>),
+    RemoveBindings(Vec<Binding<InputKind>>),
+    AddChordBindings(Vec<(KeyboardChord, BindingAction)>),
+    RemoveChordBindings(Vec<KeyboardChord>),
     ProxyToBackend(backend::Command),
+    ProcessBindingAction(BindingAction),
+    BlinkCursor,
+    ProcessBackendFailure(BackendError),
+}
+
+impl From<Event> for Command {
+    fn from(event: Event) -> Self {
+        match event {
+            Event::BackendCall(_, cmd) => Command::ProxyToBackend(cmd),
+            Event::BindingCall(_, action) => {
+                Command::ProcessBindingAction(action)
+            },
+            Event::CursorBlink(_) => Command::BlinkCursor,
+            Event::BackendFailed(_, error) => {
+                Command::ProcessBackendFailure(error)
+            },
+        }
+    }
 }
 
 pub struct Terminal {
@@ -33,45 +78,93 @@ pub struct Terminal {
     widget_id: iced::widget::Id,
     pub(crate) font: TermFont,
     pub(crate) theme: Theme,
-    pub(crate) cache: Cache,
+    pub(crate) line_caches: Vec<Cache>,
     pub(crate) bindings: BindingsLayout,
+    pub(crate) keyboard: KeyboardSettings,
+    pub(crate) cursor_blink: CursorBlink,
     pub(crate) backend: backend::Backend,
-    backend_event_rx: Arc<Mutex<Receiver<AlacrittyEvent>>>,
+    backend_event_rx: Arc<Mutex<EventReceiver>>,
 }
 
 impl Terminal {
+    /// Invalid theme colors fall back to the default ones,
+    /// check them with [`ColorPalette::validate`] first
     pub fn new(id: u64, settings: Settings) -> Result<Self> {
-        let (backend_event_tx, backend_event_rx) = mpsc::channel(100);
+        let (event_proxy, backend_event_rx) = backend::event_channel();
         let theme = Theme::new(settings.theme);
         let font = TermFont::new(settings.font);
+        let mut bindings = BindingsLayout::default();
+        bindings.modify_other_keys = settings.keyboard.modify_other_keys;
 
-        Ok(Self {
+        let mut terminal = Self {
             id,
             widget_id: iced::widget::Id::unique(),
             font,
             theme,
-            bindings: BindingsLayout::default(),
-            cache: Cache::default(),
+            bindings,
+            keyboard: settings.keyboard,
+            cursor_blink: CursorBlink::new(&settings.cursor, Instant::now()),
+            line_caches: Vec::new(),
             backend: backend::Backend::new(
                 id,
-                backend_event_tx,
+                event_proxy,
                 settings.backend,
+                (&settings.cursor).into(),
             )?,
             backend_event_rx: Arc::new(Mutex::new(backend_event_rx)),
-        })
+        };
+        terminal.redraw();
+
+        Ok(terminal)
     }
 
     pub fn widget_id(&self) -> &iced::widget::Id {
         &self.widget_id
     }
 
+    /// The bindings that are triggered in the current terminal mode,
+    /// for example to show them on a keyboard shortcuts screen
+    pub fn active_bindings(&self) -> Vec<(Binding<InputKind>, BindingAction)> {
+        let terminal_mode = self.backend.renderable_content().terminal_mode;
+        self.bindings
+            .active_bindings(terminal_mode)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
     pub fn subscription(&self) -> Subscription<Event> {
         let data = TerminalSubscriptionData {
             id: self.id,
             event_receiver: self.backend_event_rx.clone(),
         };
 
-        Subscription::run_with(data, terminal_subscription_stream)
+        let backend_subscription =
+            Subscription::run_with(data, terminal_subscription_stream);
+        if !self.is_cursor_blinking(Instant::now()) {
+            return backend_subscription;
+        }
+
+        let blink_subscription = iced::time::every(self.cursor_blink.interval)
+            .with(self.id)
+            .map(|(id, _)| Event::CursorBlink(id));
+
+        Subscription::batch([backend_subscription, blink_subscription])
+    }
+
+    /// Whether the cursor is shown in the current blinking phase
+    pub(crate) fn is_cursor_visible(&self, now: Instant) -> bool {
+        let content = self.backend.renderable_content();
+        self.cursor_blink
+            .is_visible(content.cursor_style.blinking, now)
+    }
+
+    fn is_cursor_blinking(&self, now: Instant) -> bool {
+        let content = self.backend.renderable_content();
+        content.terminal_mode.contains(TermMode::SHOW_CURSOR)
+            && self
+                .cursor_blink
+                .is_active(content.cursor_style.blinking, now)
     }
 
     pub fn handle(&mut self, cmd: Command) -> Action {
@@ -79,23 +172,96 @@ impl Terminal {
 
         match cmd {
             Command::ChangeTheme(color_pallete) => {
-                self.theme = Theme::new(ThemeSettings::new(color_pallete));
+                self.theme = Theme::new(ThemeSettings {
+                    draw_bold_text_with_bright_colors: self
+                        .theme
+                        .draw_bold_text_with_bright_colors(),
+                    ..ThemeSettings::new(color_pallete)
+                });
+                self.backend.damage_all();
             },
             Command::ChangeFont(font_settings) => {
                 self.font = TermFont::new(font_settings);
+                self.backend.damage_all();
             },
             Command::AddBindings(bindings) => {
                 self.bindings.add_bindings(bindings);
             },
+            Command::RemoveBindings(bindings) => {
+                self.bindings.remove_bindings(bindings);
+            },
+            Command::AddChordBindings(chords) => {
+                self.bindings.add_chord_bindings(chords);
+            },
+            Command::RemoveChordBindings(chords) => {
+                self.bindings.remove_chord_bindings(chords);
+            },
             Command::ProxyToBackend(cmd) => {
+                if let backend::Command::Write(_) = cmd {
+                    self.cursor_blink.reset(Instant::now());
+                }
                 action = self.backend.handle(cmd);
             },
+            Command::ProcessBindingAction(binding_action) => {
+                action = self.process_binding_action(binding_action);
+            },
+            Command::BlinkCursor => {
+                // The cursor is not cached by the view,
+                // so the grid doesn't need to be synced and redrawn
+                let blinking =
+                    self.backend.renderable_content().cursor_style.blinking;
+                self.cursor_blink.tick(blinking, Instant::now());
+                return action;
+            },
+            Command::ProcessBackendFailure(error) => {
+                return Action::BackendFailed(error);
+            },
         };
 
         self.sync_and_redraw();
         action
     }
 
+    fn process_binding_action(&mut self, action: BindingAction) -> Action {
+        let cmd = match action {
+            BindingAction::Char(c) => {
+                let mut buf = [0, 0, 0, 0];
+                let str = c.encode_utf8(&mut buf);
+                backend::Command::Write(str.as_bytes().to_vec())
+            },
+            BindingAction::Esc(seq) => {
+                backend::Command::Write(seq.as_bytes().to_vec())
+            },
+            BindingAction::ScrollPageUp => backend::Command::ScrollPageUp,
+            BindingAction::ScrollPageDown => backend::Command::ScrollPageDown,
+            BindingAction::ScrollToTop => backend::Command::ScrollToTop,
+            BindingAction::ScrollToBottom => backend::Command::ScrollToBottom,
+            BindingAction::ClearHistory => backend::Command::ClearHistory,
+            BindingAction::SelectAll => backend::Command::SelectAll,
+            BindingAction::ResetTerminal => backend::Command::Reset,
+            BindingAction::IncreaseFontSize => {
+                self.font.increase_size();
+                return Action::Ignore;
+            },
+            BindingAction::DecreaseFontSize => {
+                self.font.decrease_size();
+                return Action::Ignore;
+            },
+            BindingAction::ResetFontSize => {
+                self.font.reset_size();
+                return Action::Ignore;
+            },
+            BindingAction::Custom(name) => return Action::Custom(name),
+            // Clipboard and links are handled by the view itself
+            BindingAction::Copy
+            | BindingAction::Paste
+            | BindingAction::LinkOpen
+            | BindingAction::Ignore => return Action::Ignore,
+        };
+
+        self.backend.handle(cmd)
+    }
+
     fn sync_and_redraw(&mut self) {
         self.sync_font();
         self.backend.sync();
@@ -109,14 +275,32 @@ impl Terminal {
     }
 
     fn redraw(&mut self) {
-        self.cache.clear();
+        let screen_lines =
+            self.backend.renderable_content().grid.screen_lines();
+        let damage = self.backend.take_damage();
+        if self.line_caches.len() != screen_lines {
+            self.line_caches.resize_with(screen_lines, Cache::default);
+            self.line_caches.iter().for_each(Cache::clear);
+            return;
+        }
+
+        match damage {
+            Damage::Full => self.line_caches.iter().for_each(Cache::clear),
+            Damage::Lines(lines) => lines
+                .into_iter()
+                .filter_map(|line| self.line_caches.get(line))
+                .for_each(Cache::clear),
+        }
     }
 }
 
+/// The minimal interval between the syncs caused by the terminal wakeups
+const FRAME_DURATION: Duration = Duration::from_millis(16);
+
 #[derive(Clone)]
 struct TerminalSubscriptionData {
     id: u64,
-    event_receiver: Arc<Mutex<Receiver<AlacrittyEvent>>>,
+    event_receiver: Arc<Mutex<EventReceiver>>,
 }
 
 impl Hash for TerminalSubscriptionData {
@@ -131,29 +315,209 @@ fn terminal_subscription_stream(
     let id = data.id;
     let event_receiver = data.event_receiver.clone();
     iced::stream::channel(1000, async move |mut output| {
-        let mut shutdown = false;
+        let mut has_child_exited = false;
+        // The terminal is synced at most once per frame on wakeups,
+        // the other events are sent immediately
+        let mut last_wakeup = Instant::now()
+            .checked_sub(FRAME_DURATION)
+            .unwrap_or_else(Instant::now);
+        let mut has_pending_wakeup = false;
         loop {
             let mut event_receiver = event_receiver.lock().await;
-            match event_receiver.recv().await {
-                Some(event) => {
-                    if let AlacrittyEvent::Exit = event {
-                        shutdown = true
-                    };
-
-                    output
-                        .send(Event::BackendCall(id, backend::Command::ProcessAlacrittyEvent(event)))
-                        .await
-                        .unwrap_or_else(|_| {
-                            panic!("iced_term stream {}: sending BackendEventReceived event is failed", id)
-                        });
+            let next_wakeup = last_wakeup + FRAME_DURATION;
+            let event = if has_pending_wakeup {
+                match tokio::time::timeout_at(
+                    next_wakeup.into(),
+                    event_receiver.recv(),
+                )
+                .await
+                {
+                    Ok(event) => event,
+                    Err(_) => {
+                        Some(BackendEvent::Terminal(AlacrittyEvent::Wakeup))
+                    },
+                }
+            } else {
+                event_receiver.recv().await
+            };
+
+            let event = match event {
+                Some(BackendEvent::Terminal(event)) => event,
+                Some(BackendEvent::Stopped) => {
+                    // The event loop has stopped before the terminal exit
+                    if has_pending_wakeup {
+                        let wakeup = AlacrittyEvent::Wakeup;
+                        let _ =
+                            send_backend_event(&mut output, id, wakeup).await;
+                    }
+
+                    let error = BackendError::PtyFailed;
+                    let _ = output.send(Event::BackendFailed(id, error)).await;
+                    return;
                 },
-                None => {
-                    if !shutdown {
-                        panic!("iced_term stream {}: terminal event channel closed unexpected", id);
+                // The terminal is dropped
+                None => return,
+            };
+
+            match event {
+                AlacrittyEvent::Wakeup => {
+                    let now = Instant::now();
+                    if now < next_wakeup {
+                        has_pending_wakeup = true;
+                        continue;
                     }
+
+                    has_pending_wakeup = false;
+                    last_wakeup = now;
                 },
+                AlacrittyEvent::ChildExit(_) => has_child_exited = true,
+                AlacrittyEvent::Exit => {
+                    // The last output is synced before the exit
+                    if has_pending_wakeup {
+                        let wakeup = AlacrittyEvent::Wakeup;
+                        let _ =
+                            send_backend_event(&mut output, id, wakeup).await;
+                    }
+
+                    // The exit code is missing when the child is killed
+                    if !has_child_exited {
+                        let error = BackendError::ChildKilled;
+                        let _ =
+                            output.send(Event::BackendFailed(id, error)).await;
+                    }
+
+                    let _ = send_backend_event(&mut output, id, event).await;
+                    return;
+                },
+                _ => {},
+            }
+
+            // The host has dropped the subscription
+            if send_backend_event(&mut output, id, event).await.is_err() {
+                return;
             }
         }
     })
     .boxed()
 }
+
+async fn send_backend_event(
+    output: &mut Sender<Event>,
+    id: u64,
+    event: AlacrittyEvent,
+) -> std::result::Result<(), SendError> {
+    output
+        .send(Event::BackendCall(
+            id,
+            backend::Command::ProcessAlacrittyEvent(event),
+        ))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::event::EventListener;
+
+    #[tokio::test]
+    async fn coalesce_wakeups() {
+        let (proxy, rx) = backend::event_channel();
+        let data = TerminalSubscriptionData {
+            id: 0,
+            event_receiver: Arc::new(Mutex::new(rx)),
+        };
+        let mut events =
+            terminal_subscription_stream(&data).map(|event| match event {
+                Event::BackendCall(
+                    _,
+                    backend::Command::ProcessAlacrittyEvent(event),
+                ) => event,
+                _ => unreachable!(),
+            });
+
+        // The wakeups are merged until the first one is received
+        for _ in 0..100 {
+            proxy.send_event(AlacrittyEvent::Wakeup);
+        }
+        proxy.send_event(AlacrittyEvent::Title(String::from("first")));
+        assert!(matches!(events.next().await, Some(AlacrittyEvent::Wakeup)));
+        assert!(matches!(
+            events.next().await,
+            Some(AlacrittyEvent::Title(title)) if title == "first"
+        ));
+
+        // The wakeup in the same frame is sent after the next events
+        proxy.send_event(AlacrittyEvent::Wakeup);
+        proxy.send_event(AlacrittyEvent::Title(String::from("second")));
+        assert!(matches!(
+            events.next().await,
+            Some(AlacrittyEvent::Title(title)) if title == "second"
+        ));
+        assert!(matches!(events.next().await, Some(AlacrittyEvent::Wakeup)));
+    }
+
+    #[tokio::test]
+    async fn end_stream_after_exit() {
+        let (proxy, rx) = backend::event_channel();
+        let data = TerminalSubscriptionData {
+            id: 0,
+            event_receiver: Arc::new(Mutex::new(rx)),
+        };
+
+        proxy.send_event(AlacrittyEvent::ChildExit(0));
+        proxy.send_event(AlacrittyEvent::Exit);
+        let events: Vec<_> =
+            terminal_subscription_stream(&data).collect().await;
+        assert!(matches!(
+            events.as_slice(),
+            [
+                Event::BackendCall(
+                    0,
+                    backend::Command::ProcessAlacrittyEvent(
+                        AlacrittyEvent::ChildExit(0)
+                    )
+                ),
+                Event::BackendCall(
+                    0,
+                    backend::Command::ProcessAlacrittyEvent(
+                        AlacrittyEvent::Exit
+                    )
+                ),
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn report_backend_failures() {
+        let (proxy, rx) = backend::event_channel();
+        let data = TerminalSubscriptionData {
+            id: 0,
+            event_receiver: Arc::new(Mutex::new(rx)),
+        };
+
+        // The killed child exits without the exit code
+        proxy.send_event(AlacrittyEvent::Exit);
+        let events: Vec<_> =
+            terminal_subscription_stream(&data).collect().await;
+        assert!(matches!(
+            events.as_slice(),
+            [
+                Event::BackendFailed(0, BackendError::ChildKilled),
+                Event::BackendCall(
+                    0,
+                    backend::Command::ProcessAlacrittyEvent(
+                        AlacrittyEvent::Exit
+                    )
+                ),
+            ]
+        ));
+
+        proxy.send_stopped();
+        let events: Vec<_> =
+            terminal_subscription_stream(&data).collect().await;
+        assert!(matches!(
+            events.as_slice(),
+            [Event::BackendFailed(0, BackendError::PtyFailed)]
+        ));
+    }
+}
@@ -1,12 +1,29 @@
 use crate::settings::ThemeSettings;
+use alacritty_terminal::term::cell::Flags;
 use alacritty_terminal::vte::ansi::{self, NamedColor};
 use iced::{widget::container, Color};
 use std::collections::HashMap;
+use std::fmt;
+
+/// The same factor alacritty uses for the dim colors
+pub(crate) const DIM_FACTOR: f32 = 0.66;
+
+const NORMAL_COLORS: [NamedColor; 8] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+];
 
 pub(crate) trait TerminalStyle {
     fn container_style(&self) -> container::Style;
 }
 
+/// The colors are `#rgb`, `#rrggbb`, `#rrggbbaa` or `rgb(r, g, b)` strings
 #[derive(Debug, Clone)]
 pub struct ColorPalette {
     pub foreground: String,
@@ -37,6 +54,31 @@ pub struct ColorPalette {
     pub dim_magenta: String,
     pub dim_cyan: String,
     pub dim_white: String,
+    /// The cursor color, the foreground of the cursor cell by default
+    pub cursor: Option<String>,
+    /// The color of the text under the block cursor,
+    /// the background of the cell by default
+    pub cursor_text: Option<String>,
+    /// The selected cells swap their colors by default,
+    /// the unset one of these two falls back to the swapped color
+    pub selection_background: Option<String>,
+    pub selection_foreground: Option<String>,
+    /// Colors of the indexes that replace the generated ones,
+    /// the 0..=15 indexes replace the matching palette fields
+    pub indexed_colors: HashMap<u8, String>,
+    /// How the 16..=255 colors are generated
+    pub color_cube: ColorCube,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorCube {
+    /// The standard xterm 6x6x6 cube and grayscale ramp
+    #[default]
+    Xterm,
+    /// The cube corners are the background, the normal colors
+    /// and the foreground, the grayscale ramp goes from the background
+    /// to the foreground, so the 256 colors match the theme
+    Interpolated,
 }
 
 impl Default for ColorPalette {
@@ -70,31 +112,424 @@ impl Default for ColorPalette {
             dim_magenta: String::from("#704d68"),
             dim_cyan: String::from("#4d7770"),
             dim_white: String::from("#8e8e8e"),
+            cursor: None,
+            cursor_text: None,
+            selection_background: None,
+            selection_foreground: None,
+            indexed_colors: HashMap::new(),
+            color_cube: ColorCube::default(),
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorParseError {
+    /// The palette field, if the color belongs to one
+    pub field: Option<String>,
+    pub value: String,
+}
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(field) = &self.field {
+            write!(f, "invalid color \"{}\": ", field)?;
+        } else {
+            write!(f, "invalid color: ")?;
+        }
+        write!(
+            f,
+            "\"{}\", expected #rgb, #rrggbb, #rrggbbaa or rgb(r, g, b)",
+            self.value
+        )
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+impl ColorPalette {
+    /// Checks that every color of the palette can be parsed
+    pub fn validate(&self) -> Result<(), ColorParseError> {
+        Palette::parse(self)?;
+        self.parse_indexed_colors()
+            .map(|(_, color)| color)
+            .collect::<Result<Vec<Color>, ColorParseError>>()
+            .map(|_| ())
+    }
+
+    fn parse_indexed_colors(
+        &self,
+    ) -> impl Iterator<Item = (u8, Result<Color, ColorParseError>)> + '_ {
+        self.indexed_colors.iter().map(|(index, value)| {
+            let color = parse_color(value).map_err(|err| ColorParseError {
+                field: Some(format!("indexed_colors[{}]", index)),
+                ..err
+            });
+            (*index, color)
+        })
+    }
+}
+
+/// The colors of a [`ColorPalette`] parsed once
+/// instead of on every lookup
+#[derive(Debug, Clone, PartialEq)]
+struct Palette {
+    foreground: Color,
+    background: Color,
+    black: Color,
+    red: Color,
+    green: Color,
+    yellow: Color,
+    blue: Color,
+    magenta: Color,
+    cyan: Color,
+    white: Color,
+    bright_black: Color,
+    bright_red: Color,
+    bright_green: Color,
+    bright_yellow: Color,
+    bright_blue: Color,
+    bright_magenta: Color,
+    bright_cyan: Color,
+    bright_white: Color,
+    bright_foreground: Option<Color>,
+    dim_foreground: Color,
+    dim_black: Color,
+    dim_red: Color,
+    dim_green: Color,
+    dim_yellow: Color,
+    dim_blue: Color,
+    dim_magenta: Color,
+    dim_cyan: Color,
+    dim_white: Color,
+    cursor: Option<Color>,
+    cursor_text: Option<Color>,
+    selection_background: Option<Color>,
+    selection_foreground: Option<Color>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::parse(&ColorPalette::default())
+            .expect("default palette colors are valid")
+    }
+}
+
+impl Palette {
+    /// Replaces the normal and bright colors with the 0..=15 indexed ones
+    fn apply_indexed_colors(&mut self, indexed_colors: &[(u8, Color)]) {
+        for (index, color) in indexed_colors {
+            let field = match index {
+                0 => &mut self.black,
+                1 => &mut self.red,
+                2 => &mut self.green,
+                3 => &mut self.yellow,
+                4 => &mut self.blue,
+                5 => &mut self.magenta,
+                6 => &mut self.cyan,
+                7 => &mut self.white,
+                8 => &mut self.bright_black,
+                9 => &mut self.bright_red,
+                10 => &mut self.bright_green,
+                11 => &mut self.bright_yellow,
+                12 => &mut self.bright_blue,
+                13 => &mut self.bright_magenta,
+                14 => &mut self.bright_cyan,
+                15 => &mut self.bright_white,
+                _ => continue,
+            };
+            *field = *color;
+        }
+    }
+
+    fn parse(palette: &ColorPalette) -> Result<Self, ColorParseError> {
+        let color = |field: &'static str, value: &str| {
+            parse_color(value).map_err(|err| ColorParseError {
+                field: Some(field.to_string()),
+                ..err
+            })
+        };
+        Self::build(
+            palette,
+            |field, value, _| color(field, value),
+            |field, value| value.map(|value| color(field, value)).transpose(),
+        )
+    }
+
+    /// Invalid colors are replaced with the default palette ones,
+    /// the invalid optional colors are left unset
+    fn parse_or_default(palette: &ColorPalette) -> Self {
+        let result = Self::build(
+            palette,
+            |_, value, default| {
+                parse_color(value).or_else(|_| parse_color(default))
+            },
+            |_, value| Ok(value.and_then(|value| parse_color(value).ok())),
+        );
+        result.unwrap_or_default()
+    }
+
+    fn build(
+        palette: &ColorPalette,
+        color: impl Fn(&'static str, &str, &str) -> Result<Color, ColorParseError>,
+        optional_color: impl Fn(
+            &'static str,
+            Option<&str>,
+        ) -> Result<Option<Color>, ColorParseError>,
+    ) -> Result<Self, ColorParseError> {
+        let default = ColorPalette::default();
+        Ok(Self {
+            foreground: color(
+                "foreground",
+                &palette.foreground,
+                &default.foreground,
+            )?,
+            background: color(
+                "background",
+                &palette.background,
+                &default.background,
+            )?,
+            black: color("black", &palette.black, &default.black)?,
+            red: color("red", &palette.red, &default.red)?,
+            green: color("green", &palette.green, &default.green)?,
+            yellow: color("yellow", &palette.yellow, &default.yellow)?,
+            blue: color("blue", &palette.blue, &default.blue)?,
+            magenta: color("magenta", &palette.magenta, &default.magenta)?,
+            cyan: color("cyan", &palette.cyan, &default.cyan)?,
+            white: color("white", &palette.white, &default.white)?,
+            bright_black: color(
+                "bright_black",
+                &palette.bright_black,
+                &default.bright_black,
+            )?,
+            bright_red: color(
+                "bright_red",
+                &palette.bright_red,
+                &default.bright_red,
+            )?,
+            bright_green: color(
+                "bright_green",
+                &palette.bright_green,
+                &default.bright_green,
+            )?,
+            bright_yellow: color(
+                "bright_yellow",
+                &palette.bright_yellow,
+                &default.bright_yellow,
+            )?,
+            bright_blue: color(
+                "bright_blue",
+                &palette.bright_blue,
+                &default.bright_blue,
+            )?,
+            bright_magenta: color(
+                "bright_magenta",
+                &palette.bright_magenta,
+                &default.bright_magenta,
+            )?,
+            bright_cyan: color(
+                "bright_cyan",
+                &palette.bright_cyan,
+                &default.bright_cyan,
+            )?,
+            bright_white: color(
+                "bright_white",
+                &palette.bright_white,
+                &default.bright_white,
+            )?,
+            bright_foreground: palette
+                .bright_foreground
+                .as_ref()
+                .map(|value| {
+                    color("bright_foreground", value, &default.foreground)
+                })
+                .transpose()?,
+            dim_foreground: color(
+                "dim_foreground",
+                &palette.dim_foreground,
+                &default.dim_foreground,
+            )?,
+            dim_black: color(
+                "dim_black",
+                &palette.dim_black,
+                &default.dim_black,
+            )?,
+            dim_red: color("dim_red", &palette.dim_red, &default.dim_red)?,
+            dim_green: color(
+                "dim_green",
+                &palette.dim_green,
+                &default.dim_green,
+            )?,
+            dim_yellow: color(
+                "dim_yellow",
+                &palette.dim_yellow,
+                &default.dim_yellow,
+            )?,
+            dim_blue: color("dim_blue", &palette.dim_blue, &default.dim_blue)?,
+            dim_magenta: color(
+                "dim_magenta",
+                &palette.dim_magenta,
+                &default.dim_magenta,
+            )?,
+            dim_cyan: color("dim_cyan", &palette.dim_cyan, &default.dim_cyan)?,
+            dim_white: color(
+                "dim_white",
+                &palette.dim_white,
+                &default.dim_white,
+            )?,
+            cursor: optional_color("cursor", palette.cursor.as_deref())?,
+            cursor_text: optional_color(
+                "cursor_text",
+                palette.cursor_text.as_deref(),
+            )?,
+            selection_background: optional_color(
+                "selection_background",
+                palette.selection_background.as_deref(),
+            )?,
+            selection_foreground: optional_color(
+                "selection_foreground",
+                palette.selection_foreground.as_deref(),
+            )?,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Theme {
-    palette: Box<ColorPalette>,
+    palette: Palette,
     ansi256_colors: HashMap<u8, Color>,
+    draw_bold_text_with_bright_colors: bool,
 }
 
 impl Default for Theme {
     fn default() -> Self {
+        let palette = Palette::default();
         Self {
-            palette: Box::<ColorPalette>::default(),
-            ansi256_colors: build_ansi256_colors(),
+            ansi256_colors: build_ansi256_colors(
+                &palette,
+                ColorCube::default(),
+                Vec::new(),
+            ),
+            palette,
+            draw_bold_text_with_bright_colors: false,
         }
     }
 }
 
 impl Theme {
+    /// Invalid palette colors are replaced with the default ones,
+    /// use [`Theme::try_new`] to get the error instead.
     pub fn new(settings: ThemeSettings) -> Self {
+        let color_palette = &settings.color_pallete;
+        let mut palette = Palette::parse_or_default(color_palette);
+        let indexed_colors: Vec<(u8, Color)> = color_palette
+            .parse_indexed_colors()
+            .filter_map(|(index, color)| Some((index, color.ok()?)))
+            .collect();
+        palette.apply_indexed_colors(&indexed_colors);
         Self {
-            palette: settings.color_pallete,
-            ansi256_colors: build_ansi256_colors(),
+            ansi256_colors: build_ansi256_colors(
+                &palette,
+                color_palette.color_cube,
+                indexed_colors,
+            ),
+            palette,
+            draw_bold_text_with_bright_colors: settings
+                .draw_bold_text_with_bright_colors,
+        }
+    }
+
+    pub fn try_new(settings: ThemeSettings) -> Result<Self, ColorParseError> {
+        let color_palette = &settings.color_pallete;
+        let mut palette = Palette::parse(color_palette)?;
+        let indexed_colors = color_palette
+            .parse_indexed_colors()
+            .map(|(index, color)| Ok((index, color?)))
+            .collect::<Result<Vec<(u8, Color)>, ColorParseError>>()?;
+        palette.apply_indexed_colors(&indexed_colors);
+        Ok(Self {
+            ansi256_colors: build_ansi256_colors(
+                &palette,
+                color_palette.color_cube,
+                indexed_colors,
+            ),
+            palette,
+            draw_bold_text_with_bright_colors: settings
+                .draw_bold_text_with_bright_colors,
+        })
+    }
+
+    pub fn draw_bold_text_with_bright_colors(&self) -> bool {
+        self.draw_bold_text_with_bright_colors
+    }
+
+    /// The text color of the cell with the given flags, the dim text
+    /// gets the dim colors and the bold one can get the bright colors
+    pub(crate) fn get_foreground(
+        &self,
+        color: ansi::Color,
+        flags: Flags,
+    ) -> Color {
+        let is_dim = flags.contains(Flags::DIM);
+        let is_bright = self.draw_bold_text_with_bright_colors
+            && flags.contains(Flags::BOLD);
+
+        match color {
+            ansi::Color::Named(named) if is_dim => {
+                self.get_color(ansi::Color::Named(named.to_dim()))
+            },
+            ansi::Color::Named(named) if is_bright => {
+                self.get_color(ansi::Color::Named(named.to_bright()))
+            },
+            ansi::Color::Indexed(index @ 0..=7) if is_dim => {
+                let named = NORMAL_COLORS[index as usize];
+                self.get_color(ansi::Color::Named(named.to_dim()))
+            },
+            ansi::Color::Indexed(index @ 0..=7) if is_bright => {
+                self.get_color(ansi::Color::Indexed(index + 8))
+            },
+            color if is_dim => {
+                let color = self.get_color(color);
+                Color {
+                    r: color.r * DIM_FACTOR,
+                    g: color.g * DIM_FACTOR,
+                    b: color.b * DIM_FACTOR,
+                    ..color
+                }
+            },
+            color => self.get_color(color),
+        }
+    }
+
+    /// The cursor and the block cursor text colors
+    /// for the cell with the given colors
+    pub(crate) fn cursor_colors(
+        &self,
+        cell_fg: Color,
+        cell_bg: Color,
+    ) -> (Color, Color) {
+        (
+            self.palette.cursor.unwrap_or(cell_fg),
+            self.palette.cursor_text.unwrap_or(cell_bg),
+        )
+    }
+
+    /// The foreground and background of the selected cell
+    /// with the given colors, `None` without the selection colors
+    pub(crate) fn selection_colors(
+        &self,
+        fg: Color,
+        bg: Color,
+    ) -> Option<(Color, Color)> {
+        if self.palette.selection_foreground.is_none()
+            && self.palette.selection_background.is_none()
+        {
+            return None;
         }
+
+        Some((
+            self.palette.selection_foreground.unwrap_or(bg),
+            self.palette.selection_background.unwrap_or(fg),
+        ))
     }
 
     pub fn get_color(&self, c: ansi::Color) -> Color {
@@ -102,30 +537,27 @@ impl Theme {
             ansi::Color::Spec(rgb) => Color::from_rgb8(rgb.r, rgb.g, rgb.b),
             ansi::Color::Indexed(index) => {
                 if index <= 15 {
-                    let color = match index {
+                    return match index {
                         // Normal terminal colors
-                        0 => &self.palette.black,
-                        1 => &self.palette.red,
-                        2 => &self.palette.green,
-                        3 => &self.palette.yellow,
-                        4 => &self.palette.blue,
-                        5 => &self.palette.magenta,
-                        6 => &self.palette.cyan,
-                        7 => &self.palette.white,
+                        0 => self.palette.black,
+                        1 => self.palette.red,
+                        2 => self.palette.green,
+                        3 => self.palette.yellow,
+                        4 => self.palette.blue,
+                        5 => self.palette.magenta,
+                        6 => self.palette.cyan,
+                        7 => self.palette.white,
                         // Bright terminal colors
-                        8 => &self.palette.bright_black,
-                        9 => &self.palette.bright_red,
-                        10 => &self.palette.bright_green,
-                        11 => &self.palette.bright_yellow,
-                        12 => &self.palette.bright_blue,
-                        13 => &self.palette.bright_magenta,
-                        14 => &self.palette.bright_cyan,
-                        15 => &self.palette.bright_white,
-                        _ => &self.palette.background,
+                        8 => self.palette.bright_black,
+                        9 => self.palette.bright_red,
+                        10 => self.palette.bright_green,
+                        11 => self.palette.bright_yellow,
+                        12 => self.palette.bright_blue,
+                        13 => self.palette.bright_magenta,
+                        14 => self.palette.bright_cyan,
+                        15 => self.palette.bright_white,
+                        _ => self.palette.background,
                     };
-
-                    return hex_to_color(color)
-                        .unwrap_or_else(|_| panic!("invalid color {}", color));
                 }
 
                 // Other colors
@@ -134,55 +566,61 @@ impl Theme {
                     None => Color::from_rgb8(0, 0, 0),
                 }
             },
-            ansi::Color::Named(c) => {
-                let color = match c {
-                    NamedColor::Foreground => &self.palette.foreground,
-                    NamedColor::Background => &self.palette.background,
-                    // Normal terminal colors
-                    NamedColor::Black => &self.palette.black,
-                    NamedColor::Red => &self.palette.red,
-                    NamedColor::Green => &self.palette.green,
-                    NamedColor::Yellow => &self.palette.yellow,
-                    NamedColor::Blue => &self.palette.blue,
-                    NamedColor::Magenta => &self.palette.magenta,
-                    NamedColor::Cyan => &self.palette.cyan,
-                    NamedColor::White => &self.palette.white,
-                    // Bright terminal colors
-                    NamedColor::BrightBlack => &self.palette.bright_black,
-                    NamedColor::BrightRed => &self.palette.bright_red,
-                    NamedColor::BrightGreen => &self.palette.bright_green,
-                    NamedColor::BrightYellow => &self.palette.bright_yellow,
-                    NamedColor::BrightBlue => &self.palette.bright_blue,
-                    NamedColor::BrightMagenta => &self.palette.bright_magenta,
-                    NamedColor::BrightCyan => &self.palette.bright_cyan,
-                    NamedColor::BrightWhite => &self.palette.bright_white,
-                    NamedColor::BrightForeground => {
-                        match &self.palette.bright_foreground {
-                            Some(color) => color,
-                            None => &self.palette.foreground,
-                        }
-                    },
-                    // Dim terminal colors
-                    NamedColor::DimForeground => &self.palette.dim_foreground,
-                    NamedColor::DimBlack => &self.palette.dim_black,
-                    NamedColor::DimRed => &self.palette.dim_red,
-                    NamedColor::DimGreen => &self.palette.dim_green,
-                    NamedColor::DimYellow => &self.palette.dim_yellow,
-                    NamedColor::DimBlue => &self.palette.dim_blue,
-                    NamedColor::DimMagenta => &self.palette.dim_magenta,
-                    NamedColor::DimCyan => &self.palette.dim_cyan,
-                    NamedColor::DimWhite => &self.palette.dim_white,
-                    _ => &self.palette.background,
-                };
-
-                hex_to_color(color)
-                    .unwrap_or_else(|_| panic!("invalid color {}", color))
+            ansi::Color::Named(c) => match c {
+                NamedColor::Foreground => self.palette.foreground,
+                NamedColor::Background => self.palette.background,
+                // Normal terminal colors
+                NamedColor::Black => self.palette.black,
+                NamedColor::Red => self.palette.red,
+                NamedColor::Green => self.palette.green,
+                NamedColor::Yellow => self.palette.yellow,
+                NamedColor::Blue => self.palette.blue,
+                NamedColor::Magenta => self.palette.magenta,
+                NamedColor::Cyan => self.palette.cyan,
+                NamedColor::White => self.palette.white,
+                // Bright terminal colors
+                NamedColor::BrightBlack => self.palette.bright_black,
+                NamedColor::BrightRed => self.palette.bright_red,
+                NamedColor::BrightGreen => self.palette.bright_green,
+                NamedColor::BrightYellow => self.palette.bright_yellow,
+                NamedColor::BrightBlue => self.palette.bright_blue,
+                NamedColor::BrightMagenta => self.palette.bright_magenta,
+                NamedColor::BrightCyan => self.palette.bright_cyan,
+                NamedColor::BrightWhite => self.palette.bright_white,
+                NamedColor::BrightForeground => self
+                    .palette
+                    .bright_foreground
+                    .unwrap_or(self.palette.foreground),
+                // Dim terminal colors
+                NamedColor::DimForeground => self.palette.dim_foreground,
+                NamedColor::DimBlack => self.palette.dim_black,
+                NamedColor::DimRed => self.palette.dim_red,
+                NamedColor::DimGreen => self.palette.dim_green,
+                NamedColor::DimYellow => self.palette.dim_yellow,
+                NamedColor::DimBlue => self.palette.dim_blue,
+                NamedColor::DimMagenta => self.palette.dim_magenta,
+                NamedColor::DimCyan => self.palette.dim_cyan,
+                NamedColor::DimWhite => self.palette.dim_white,
+                _ => self.palette.background,
             },
         }
     }
 }
 
-fn build_ansi256_colors() -> HashMap<u8, Color> {
+fn build_ansi256_colors(
+    palette: &Palette,
+    color_cube: ColorCube,
+    indexed_colors: Vec<(u8, Color)>,
+) -> HashMap<u8, Color> {
+    let mut ansi256_colors = match color_cube {
+        ColorCube::Xterm => build_xterm_colors(),
+        ColorCube::Interpolated => build_interpolated_colors(palette),
+    };
+    ansi256_colors.extend(indexed_colors);
+    ansi256_colors
+}
+
+fn build_xterm_colors() -> HashMap<u8, Color> {
     let mut ansi256_colors = HashMap::new();
 
     for r in 0..6 {
@@ -209,31 +647,118 @@ fn build_ansi256_colors() -> HashMap<u8, Color> {
     ansi256_colors
 }
 
-fn hex_to_color(hex: &str) -> anyhow::Result<Color> {
-    if hex.len() != 7 {
-        return Err(anyhow::format_err!("input string is in non valid format"));
+fn build_interpolated_colors(palette: &Palette) -> HashMap<u8, Color> {
+    let mut ansi256_colors = HashMap::new();
+    // Indexed by the red, green and blue bits of the corner
+    let corners = [
+        palette.background,
+        palette.red,
+        palette.green,
+        palette.yellow,
+        palette.blue,
+        palette.magenta,
+        palette.cyan,
+        palette.foreground,
+    ];
+
+    for r in 0..6 {
+        for g in 0..6 {
+            for b in 0..6 {
+                let index = 16 + r * 36 + g * 6 + b;
+                let (r, g, b) = (
+                    f32::from(r) / 5.0,
+                    f32::from(g) / 5.0,
+                    f32::from(b) / 5.0,
+                );
+                let plane = |blue: usize| {
+                    mix(
+                        mix(corners[blue], corners[blue + 1], r),
+                        mix(corners[blue + 2], corners[blue + 3], r),
+                        g,
+                    )
+                };
+                ansi256_colors.insert(index, mix(plane(0), plane(4), b));
+            }
+        }
+    }
+
+    for i in 0..24 {
+        let t = f32::from(i + 1) / 25.0;
+        ansi256_colors
+            .insert(232 + i, mix(palette.background, palette.foreground, t));
+    }
+
+    ansi256_colors
+}
+
+fn mix(from: Color, to: Color, t: f32) -> Color {
+    Color {
+        r: from.r + (to.r - from.r) * t,
+        g: from.g + (to.g - from.g) * t,
+        b: from.b + (to.b - from.b) * t,
+        a: from.a + (to.a - from.a) * t,
     }
+}
 
-    let r = u8::from_str_radix(&hex[1..3], 16)?;
-    let g = u8::from_str_radix(&hex[3..5], 16)?;
-    let b = u8::from_str_radix(&hex[5..7], 16)?;
+/// Parses `#rgb`, `#rrggbb`, `#rrggbbaa` and `rgb(r, g, b)` colors
+pub(crate) fn parse_color(value: &str) -> Result<Color, ColorParseError> {
+    let error = || ColorParseError {
+        field: None,
+        value: value.to_string(),
+    };
+    let trimmed = value.trim();
 
-    Ok(Color::from_rgb8(r, g, b))
+    if let Some(hex) = trimmed.strip_prefix('#') {
+        if !hex.is_ascii() {
+            return Err(error());
+        }
+
+        let channel = |i: usize, len: usize| {
+            u8::from_str_radix(&hex[i * len..(i + 1) * len], 16)
+                .map(|c| if len == 1 { c * 17 } else { c })
+                .map_err(|_| error())
+        };
+        return match hex.len() {
+            3 => Ok(Color::from_rgb8(
+                channel(0, 1)?,
+                channel(1, 1)?,
+                channel(2, 1)?,
+            )),
+            6 => Ok(Color::from_rgb8(
+                channel(0, 2)?,
+                channel(1, 2)?,
+                channel(2, 2)?,
+            )),
+            8 => Ok(Color::from_rgba8(
+                channel(0, 2)?,
+                channel(1, 2)?,
+                channel(2, 2)?,
+                f32::from(channel(3, 2)?) / 255.0,
+            )),
+            _ => Err(error()),
+        };
+    }
+
+    let lowercase = trimmed.to_ascii_lowercase();
+    let components = lowercase
+        .strip_prefix("rgb(")
+        .and_then(|rest| rest.strip_suffix(')'))
+        .ok_or_else(error)?;
+    let channels = components
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|channel| !channel.is_empty())
+        .map(|channel| channel.parse::<u8>().map_err(|_| error()))
+        .collect::<Result<Vec<u8>, ColorParseError>>()?;
+    match channels[..] {
+        [r, g, b] => Ok(Color::from_rgb8(r, g, b)),
+        _ => Err(error()),
+    }
 }
 
 impl TerminalStyle for Theme {
     fn container_style(&self) -> container::Style {
         container::Style {
-            background: Some(
-                hex_to_color(&self.palette.background)
-                    .unwrap_or_else(|_| {
-                        panic!(
-                            "invalid background color {}",
-                            self.palette.background
-                        )
-                    })
-                    .into(),
-            ),
+            background: Some(self.palette.background.into()),
             ..container::Style::default()
         }
     }
@@ -246,51 +771,352 @@ mod tests {
     use std::collections::HashMap;
 
     #[test]
-    fn hex_to_color_valid_convertion() {
-        assert!(hex_to_color("#000000").is_ok())
+    fn parse_color_valid_convertion() {
+        assert!(parse_color("#000000").is_ok())
+    }
+
+    #[test]
+    fn parse_color_short_string() {
+        assert!(parse_color("GG").is_err());
     }
 
     #[test]
-    fn hex_to_color_short_string() {
-        assert!(hex_to_color("GG").is_err());
+    fn parse_color_long_string() {
+        assert!(parse_color("GG000000").is_err());
     }
 
     #[test]
-    fn hex_to_color_long_string() {
-        assert!(hex_to_color("GG000000").is_err());
+    fn parse_color_non_valid_hex_string() {
+        assert!(parse_color("#KKLLOO").is_err());
     }
 
     #[test]
-    fn hex_to_color_non_valid_hex_string() {
-        assert!(hex_to_color("#KKLLOO").is_err());
+    fn parse_color_forms() {
+        let cases = vec![
+            ("#fff", Color::from_rgb8(255, 255, 255)),
+            ("#1a2", Color::from_rgb8(0x11, 0xaa, 0x22)),
+            ("#300A24", Color::from_rgb8(0x30, 0x0a, 0x24)),
+            (" #300a24 ", Color::from_rgb8(0x30, 0x0a, 0x24)),
+            ("#ff000080", Color::from_rgba8(255, 0, 0, 128.0 / 255.0)),
+            ("rgb(1, 2, 3)", Color::from_rgb8(1, 2, 3)),
+            ("RGB(255 128 0)", Color::from_rgb8(255, 128, 0)),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(parse_color(value), Ok(expected), "{}", value);
+        }
+
+        for value in [
+            "",
+            "#ff",
+            "#fffff",
+            "#ff00008",
+            "rgb(1, 2)",
+            "rgb(1, 2, 256)",
+            "rgb(1, 2, 3",
+            "#ffé",
+            "red",
+        ] {
+            assert_eq!(
+                parse_color(value),
+                Err(ColorParseError {
+                    field: None,
+                    value: value.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn validate_palette() {
+        assert_eq!(ColorPalette::default().validate(), Ok(()));
+
+        let palette = ColorPalette {
+            red: String::from("#ac424"),
+            ..Default::default()
+        };
+        let err = palette.validate().unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("red"));
+        assert_eq!(
+            err.to_string(),
+            "invalid color \"red\": \"#ac424\", expected #rgb, #rrggbb, #rrggbbaa or rgb(r, g, b)"
+        );
+        assert!(
+            Theme::try_new(ThemeSettings::new(Box::new(palette.clone())))
+                .is_err()
+        );
+
+        // The invalid color does not break the rendering
+        let theme = Theme::new(ThemeSettings::new(Box::new(ColorPalette {
+            foreground: String::from("rgb(1, 2, 3)"),
+            ..palette
+        })));
+        assert_eq!(
+            theme.get_color(ansi::Color::Named(NamedColor::Red)),
+            parse_color(&ColorPalette::default().red).unwrap()
+        );
+        assert_eq!(
+            theme.get_color(ansi::Color::Named(NamedColor::Foreground)),
+            Color::from_rgb8(1, 2, 3)
+        );
+        assert_eq!(
+            theme.get_color(ansi::Color::Named(NamedColor::BrightForeground)),
+            Color::from_rgb8(1, 2, 3)
+        );
+    }
+
+    #[test]
+    fn get_dim_and_bright_foreground() {
+        let named = |c| ansi::Color::Named(c);
+        let theme = Theme::default();
+        let palette = &theme.palette;
+
+        let cases = vec![
+            (named(NamedColor::Red), Flags::empty(), palette.red),
+            (named(NamedColor::Red), Flags::DIM, palette.dim_red),
+            (named(NamedColor::Red), Flags::DIM_BOLD, palette.dim_red),
+            (named(NamedColor::Red), Flags::BOLD, palette.red),
+            (
+                named(NamedColor::Foreground),
+                Flags::DIM,
+                palette.dim_foreground,
+            ),
+            (named(NamedColor::BrightBlue), Flags::DIM, palette.blue),
+            (ansi::Color::Indexed(2), Flags::DIM, palette.dim_green),
+            (ansi::Color::Indexed(2), Flags::BOLD, palette.green),
+            (
+                ansi::Color::Spec(ansi::Rgb {
+                    r: 100,
+                    g: 200,
+                    b: 0,
+                }),
+                Flags::DIM,
+                Color::from_rgb(
+                    100.0 / 255.0 * DIM_FACTOR,
+                    200.0 / 255.0 * DIM_FACTOR,
+                    0.0,
+                ),
+            ),
+        ];
+        for (color, flags, expected) in cases {
+            assert_eq!(
+                theme.get_foreground(color, flags),
+                expected,
+                "{:?} {:?}",
+                color,
+                flags
+            );
+        }
+
+        let theme = Theme::new(ThemeSettings {
+            draw_bold_text_with_bright_colors: true,
+            ..Default::default()
+        });
+        let palette = &theme.palette;
+        let cases = vec![
+            (named(NamedColor::Red), Flags::BOLD, palette.bright_red),
+            (named(NamedColor::Red), Flags::DIM_BOLD, palette.dim_red),
+            (
+                named(NamedColor::Foreground),
+                Flags::BOLD,
+                palette.foreground,
+            ),
+            (ansi::Color::Indexed(2), Flags::BOLD, palette.bright_green),
+            (ansi::Color::Indexed(10), Flags::BOLD, palette.bright_green),
+            (ansi::Color::Indexed(2), Flags::empty(), palette.green),
+        ];
+        for (color, flags, expected) in cases {
+            assert_eq!(
+                theme.get_foreground(color, flags),
+                expected,
+                "{:?} {:?}",
+                color,
+                flags
+            );
+        }
+    }
+
+    #[test]
+    fn cursor_and_selection_colors() {
+        let fg = Color::from_rgb8(255, 255, 255);
+        let bg = Color::from_rgb8(0, 0, 0);
+
+        // The unset colors keep the cell ones
+        let theme = Theme::default();
+        assert_eq!(theme.cursor_colors(fg, bg), (fg, bg));
+        assert_eq!(theme.selection_colors(fg, bg), None);
+
+        let theme = Theme::new(ThemeSettings::new(Box::new(ColorPalette {
+            cursor: Some(String::from("#ff0000")),
+            selection_background: Some(String::from("#00ff00")),
+            ..Default::default()
+        })));
+        assert_eq!(
+            theme.cursor_colors(fg, bg),
+            (Color::from_rgb8(255, 0, 0), bg)
+        );
+        assert_eq!(
+            theme.selection_colors(fg, bg),
+            Some((bg, Color::from_rgb8(0, 255, 0)))
+        );
+
+        let palette = ColorPalette {
+            cursor_text: Some(String::from("#00ff0")),
+            ..Default::default()
+        };
+        assert_eq!(
+            palette.validate().unwrap_err().field.as_deref(),
+            Some("cursor_text")
+        );
+        // The invalid optional color is left unset
+        let theme = Theme::new(ThemeSettings::new(Box::new(palette)));
+        assert_eq!(theme.cursor_colors(fg, bg), (fg, bg));
+    }
+
+    #[test]
+    fn get_xterm_cube_colors() {
+        let theme = Theme::default();
+        let cases = [
+            (16, Color::from_rgb8(0, 0, 0)),
+            (17, Color::from_rgb8(0, 0, 95)),
+            (196, Color::from_rgb8(255, 0, 0)),
+            (231, Color::from_rgb8(255, 255, 255)),
+            (232, Color::from_rgb8(8, 8, 8)),
+            (255, Color::from_rgb8(238, 238, 238)),
+        ];
+        for (index, expected) in cases {
+            assert_eq!(theme.get_color(ansi::Color::Indexed(index)), expected);
+        }
+    }
+
+    #[test]
+    fn get_overridden_indexed_colors() {
+        let palette = ColorPalette {
+            indexed_colors: HashMap::from([
+                (3, String::from("#ffffff")),
+                (16, String::from("#002b36")),
+                (255, String::from("rgb(1, 2, 3)")),
+            ]),
+            ..Default::default()
+        };
+        let theme =
+            Theme::try_new(ThemeSettings::new(Box::new(palette.clone())))
+                .unwrap();
+        assert_eq!(
+            theme.get_color(ansi::Color::Indexed(16)),
+            Color::from_rgb8(0x00, 0x2b, 0x36)
+        );
+        assert_eq!(
+            theme.get_color(ansi::Color::Indexed(255)),
+            Color::from_rgb8(1, 2, 3)
+        );
+        // The first 16 indexes replace the palette fields
+        assert_eq!(
+            theme.get_color(ansi::Color::Indexed(3)),
+            Color::from_rgb8(0xff, 0xff, 0xff)
+        );
+        assert_eq!(
+            theme.get_color(ansi::Color::Named(NamedColor::Yellow)),
+            Color::from_rgb8(0xff, 0xff, 0xff)
+        );
+
+        let mut palette = palette;
+        palette.indexed_colors.insert(17, String::from("#00"));
+        assert_eq!(
+            palette.validate().unwrap_err().field.as_deref(),
+            Some("indexed_colors[17]")
+        );
+        // The invalid one keeps the generated color
+        let theme = Theme::new(ThemeSettings::new(Box::new(palette)));
+        assert_eq!(
+            theme.get_color(ansi::Color::Indexed(17)),
+            Color::from_rgb8(0, 0, 95)
+        );
+
+        let palette = ColorPalette {
+            indexed_colors: HashMap::from([(3, String::from("#00"))]),
+            ..Default::default()
+        };
+        assert_eq!(
+            Theme::try_new(ThemeSettings::new(Box::new(palette)))
+                .unwrap_err()
+                .field
+                .as_deref(),
+            Some("indexed_colors[3]")
+        );
+    }
+
+    #[test]
+    fn get_interpolated_cube_colors() {
+        let palette = ColorPalette {
+            background: String::from("#000000"),
+            foreground: String::from("#ffffff"),
+            red: String::from("#ff0000"),
+            blue: String::from("#0000ff"),
+            magenta: String::from("#ff00ff"),
+            color_cube: ColorCube::Interpolated,
+            ..Default::default()
+        };
+        let theme = Theme::new(ThemeSettings::new(Box::new(palette.clone())));
+        let color = |index| theme.get_color(ansi::Color::Indexed(index));
+
+        // The cube corners are the palette colors
+        let corners = [
+            (16, &palette.background),
+            (196, &palette.red),
+            (46, &palette.green),
+            (226, &palette.yellow),
+            (21, &palette.blue),
+            (201, &palette.magenta),
+            (51, &palette.cyan),
+            (231, &palette.foreground),
+        ];
+        for (index, expected) in corners {
+            assert_eq!(
+                color(index),
+                parse_color(expected).unwrap(),
+                "{}",
+                index
+            );
+        }
+
+        // Between the pure black, red, blue and magenta corners
+        let purple = color(16 + 3 * 36 + 3);
+        assert!((purple.r - 0.6).abs() < 1e-5);
+        assert!((purple.g - 0.0).abs() < 1e-5);
+        assert!((purple.b - 0.6).abs() < 1e-5);
+
+        // The grayscale ramp goes from the background to the foreground
+        assert!((color(232).r - 1.0 / 25.0).abs() < 1e-5);
+        assert!((color(255).g - 24.0 / 25.0).abs() < 1e-5);
     }
 
     #[test]
     fn get_basic_indexed_colors() {
         let default_theme = Theme::default();
+        let default_palette = ColorPalette::default();
         let basic_indexed_colors_map: HashMap<u8, String> = HashMap::from([
-            (0, default_theme.palette.black.clone()),
-            (1, default_theme.palette.red.clone()),
-            (2, default_theme.palette.green.clone()),
-            (3, default_theme.palette.yellow.clone()),
-            (4, default_theme.palette.blue.clone()),
-            (5, default_theme.palette.magenta.clone()),
-            (6, default_theme.palette.cyan.clone()),
-            (7, default_theme.palette.white.clone()),
-            (8, default_theme.palette.bright_black.clone()),
-            (9, default_theme.palette.bright_red.clone()),
-            (10, default_theme.palette.bright_green.clone()),
-            (11, default_theme.palette.bright_yellow.clone()),
-            (12, default_theme.palette.bright_blue.clone()),
-            (13, default_theme.palette.bright_magenta.clone()),
-            (14, default_theme.palette.bright_cyan.clone()),
-            (15, default_theme.palette.bright_white.clone()),
+            (0, default_palette.black.clone()),
+            (1, default_palette.red.clone()),
+            (2, default_palette.green.clone()),
+            (3, default_palette.yellow.clone()),
+            (4, default_palette.blue.clone()),
+            (5, default_palette.magenta.clone()),
+            (6, default_palette.cyan.clone()),
+            (7, default_palette.white.clone()),
+            (8, default_palette.bright_black.clone()),
+            (9, default_palette.bright_red.clone()),
+            (10, default_palette.bright_green.clone()),
+            (11, default_palette.bright_yellow.clone()),
+            (12, default_palette.bright_blue.clone()),
+            (13, default_palette.bright_magenta.clone()),
+            (14, default_palette.bright_cyan.clone()),
+            (15, default_palette.bright_white.clone()),
         ]);
 
         for index in 0..16 {
             let color = default_theme.get_color(ansi::Color::Indexed(index));
             let expected_color = basic_indexed_colors_map.get(&index).unwrap();
-            assert_eq!(color, hex_to_color(expected_color).unwrap())
+            assert_eq!(color, parse_color(expected_color).unwrap())
         }
     }
 }
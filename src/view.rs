@@ -1,24 +1,39 @@
 use crate::backend::{
     Backend, Command, LinkAction, MouseButton, RenderableContent,
 };
-use crate::bindings::{BindingAction, BindingsLayout, InputKind};
+use crate::bindings::{
+    BindingAction, BindingsLayout, ChordKey, ChordMatch, InputKind,
+};
+use crate::box_drawing::{is_builtin_glyph, BuiltinGlyph};
+use crate::cursor::cursor_shape;
+use crate::decoration::{Decoration, DecorationKind, UnderlineStyle};
+#[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+use crate::graphics::Placement;
+use crate::settings::AltAsMeta;
 use crate::terminal::{Event, Terminal};
 use crate::theme::TerminalStyle;
-use alacritty_terminal::index::Point as TerminalGridPoint;
-use alacritty_terminal::selection::SelectionType;
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point as TerminalGridPoint};
+use alacritty_terminal::selection::{SelectionRange, SelectionType};
 use alacritty_terminal::term::{cell, TermMode};
-use alacritty_terminal::vte::ansi::{self as ansi, NamedColor};
+use alacritty_terminal::vte::ansi::{self as ansi, CursorShape, NamedColor};
 use iced::alignment::Vertical;
-use iced::font::{Style as FontStyle, Weight as FontWeight};
 use iced::mouse::{Cursor, ScrollDelta};
-use iced::widget::canvas::{Path, Text};
+use iced::widget::canvas::{Frame, Path, Text};
 use iced::widget::container;
-use iced::{Color, Element, Length, Point, Rectangle, Size, Theme};
+use iced::{
+    Color, Element, Font, Length, Point, Rectangle, Size, Theme, Vector,
+};
 use iced_core::clipboard::Kind as ClipboardKind;
-use iced_core::keyboard::{Key, Modifiers};
+use iced_core::keyboard::{
+    key::{Named, Physical},
+    Key, Location, Modifiers,
+};
 use iced_core::mouse::{self, Click};
 use iced_core::text::{Alignment, LineHeight, Shaping};
+use iced_core::time::{Duration, Instant};
 use iced_core::widget::operation::{self, Focusable};
+use iced_core::window;
 use iced_graphics::core::widget::{tree, Tree};
 use iced_graphics::core::Widget;
 use iced_graphics::geometry::Stroke;
@@ -104,11 +119,13 @@ impl<'a> TerminalView<'a> {
     fn handle_mouse_event(
         &self,
         state: &mut TerminalViewState,
+        clipboard: &mut dyn iced_graphics::core::Clipboard,
         layout_position: Point,
         cursor_position: Point,
         event: &iced::mouse::Event,
-    ) -> Vec<Command> {
+    ) -> Vec<Event> {
         let mut commands = Vec::new();
+        let mut binding_action = BindingAction::Ignore;
         let terminal_content = self.term.backend.renderable_content();
         let terminal_mode = terminal_content.terminal_mode;
 
@@ -148,7 +165,7 @@ impl<'a> TerminalView<'a> {
                     return Vec::default();
                 }
 
-                Self::handle_button_released(
+                binding_action = Self::handle_button_released(
                     state,
                     &terminal_mode,
                     &self.term.bindings,
@@ -166,7 +183,12 @@ impl<'a> TerminalView<'a> {
             _ => {},
         }
 
-        commands
+        let mut events: Vec<Event> = commands
+            .into_iter()
+            .map(|cmd| self.backend_call(cmd))
+            .collect();
+        events.extend(self.perform_binding_action(clipboard, binding_action));
+        events
     }
 
     fn handle_left_button_pressed(
@@ -255,12 +277,14 @@ impl<'a> TerminalView<'a> {
         }
     }
 
+    /// Returns the action bound to the button for the caller to perform,
+    /// the links are opened here
     fn handle_button_released(
         state: &mut TerminalViewState,
         terminal_mode: &TermMode,
         bindings: &BindingsLayout, // Use the actual type of your bindings here
         commands: &mut Vec<Command>,
-    ) {
+    ) -> BindingAction {
         state.is_dragged = false;
 
         if terminal_mode.intersects(TermMode::MOUSE_MODE) {
@@ -272,16 +296,19 @@ impl<'a> TerminalView<'a> {
             ));
         }
 
-        if bindings.get_action(
+        match bindings.get_action(
             InputKind::Mouse(iced_core::mouse::Button::Left),
             state.keyboard_modifiers,
             *terminal_mode,
-        ) == BindingAction::LinkOpen
-        {
-            commands.push(Command::ProcessLink(
-                LinkAction::Open,
-                state.mouse_position_on_grid,
-            ));
+        ) {
+            BindingAction::LinkOpen => {
+                commands.push(Command::ProcessLink(
+                    LinkAction::Open,
+                    state.mouse_position_on_grid,
+                ));
+                BindingAction::Ignore
+            },
+            action => action,
         }
     }
 
@@ -313,9 +340,10 @@ impl<'a> TerminalView<'a> {
         state: &mut TerminalViewState,
         clipboard: &mut dyn iced_graphics::core::Clipboard,
         event: &iced::keyboard::Event,
-    ) -> Option<Command> {
+    ) -> Option<Event> {
         let mut binding_action = BindingAction::Ignore;
         let last_content = self.term.backend.renderable_content();
+        Self::handle_alt_side(state, event);
         match event {
             iced::keyboard::Event::ModifiersChanged(m) => {
                 state.keyboard_modifiers = *m;
@@ -324,58 +352,120 @@ impl<'a> TerminalView<'a> {
                 } else {
                     LinkAction::Clear
                 };
-                return Some(Command::ProcessLink(
+                return Some(self.backend_call(Command::ProcessLink(
                     action,
                     state.mouse_position_on_grid,
-                ));
+                )));
             },
             iced::keyboard::Event::KeyPressed {
                 key,
+                modified_key,
                 modifiers,
                 text,
+                physical_key,
                 ..
-            } => match &key {
-                // Use the physical character key for bindings even when text is None (e.g., Ctrl/Cmd combos)
-                Key::Character(k) => {
-                    let lower = k.to_ascii_lowercase();
-                    binding_action = self.term.bindings.get_action(
-                        InputKind::Char(lower),
-                        state.keyboard_modifiers,
+            } => {
+                let inputs = Self::key_inputs(
+                    key,
+                    *physical_key,
+                    state.keyboard_modifiers,
+                );
+                if !Self::is_modifier_key(key) && !inputs.is_empty() {
+                    let chord_action = Self::process_chord(
+                        &self.term.bindings,
+                        self.term.keyboard.chord_timeout,
+                        state,
+                        ChordKey {
+                            inputs: inputs.clone(),
+                            modifiers: state.keyboard_modifiers,
+                        },
                         last_content.terminal_mode,
+                        Instant::now(),
                     );
+                    if let Some(action) = chord_action {
+                        return self.perform_binding_action(clipboard, action);
+                    }
+                }
+
+                match &key {
+                    Key::Character(k) => {
+                        binding_action =
+                            self.term.bindings.get_action_with_fallbacks(
+                                &inputs,
+                                state.keyboard_modifiers,
+                                last_content.terminal_mode,
+                            );
 
-                    // If no binding matched, only write printable text (when provided)
-                    if binding_action == BindingAction::Ignore {
-                        if let Some(c) = text {
-                            return Some(Command::Write(c.as_bytes().to_vec()));
+                        if binding_action == BindingAction::Ignore
+                            && Self::is_meta_pressed(
+                                self.term.keyboard.alt_as_meta,
+                                state,
+                            )
+                        {
+                            let modified = match modified_key {
+                                Key::Character(m) => m.as_str(),
+                                _ => k.as_str(),
+                            };
+                            return Some(self.backend_call(Command::Write(
+                                Self::meta_input(
+                                    &self.term.bindings,
+                                    &inputs,
+                                    k,
+                                    modified,
+                                    state.keyboard_modifiers,
+                                    last_content.terminal_mode,
+                                ),
+                            )));
                         }
-                    }
-                },
-                Key::Named(code) => {
-                    binding_action = self.term.bindings.get_action(
-                        InputKind::KeyCode(*code),
-                        *modifiers,
-                        last_content.terminal_mode,
-                    );
-                },
-                _ => {},
+
+                        // If no binding matched, only write printable text (when provided)
+                        if binding_action == BindingAction::Ignore {
+                            if let Some(c) = text {
+                                return Some(self.backend_call(
+                                    Command::Write(c.as_bytes().to_vec()),
+                                ));
+                            }
+                        }
+                    },
+                    Key::Named(_) => {
+                        binding_action =
+                            self.term.bindings.get_action_with_fallbacks(
+                                &inputs,
+                                *modifiers,
+                                last_content.terminal_mode,
+                            );
+                    },
+                    _ => {},
+                }
             },
             _ => {},
         }
 
+        self.perform_binding_action(clipboard, binding_action)
+    }
+
+    fn perform_binding_action(
+        &self,
+        clipboard: &mut dyn iced_graphics::core::Clipboard,
+        binding_action: BindingAction,
+    ) -> Option<Event> {
         match binding_action {
             BindingAction::Char(c) => {
                 let mut buf = [0, 0, 0, 0];
                 let str = c.encode_utf8(&mut buf);
-                return Some(Command::Write(str.as_bytes().to_vec()));
+                return Some(
+                    self.backend_call(Command::Write(str.as_bytes().to_vec())),
+                );
             },
             BindingAction::Esc(seq) => {
-                return Some(Command::Write(seq.as_bytes().to_vec()));
+                return Some(
+                    self.backend_call(Command::Write(seq.as_bytes().to_vec())),
+                );
             },
             BindingAction::Paste => {
                 if let Some(data) = clipboard.read(ClipboardKind::Standard) {
                     let input: Vec<u8> = data.bytes().collect();
-                    return Some(Command::Write(input));
+                    return Some(self.backend_call(Command::Write(input)));
                 }
             },
             BindingAction::Copy => {
@@ -384,11 +474,711 @@ impl<'a> TerminalView<'a> {
                     self.term.backend.selectable_content(),
                 );
             },
-            _ => {},
+            BindingAction::Ignore | BindingAction::LinkOpen => {},
+            action => {
+                return Some(Event::BindingCall(self.term.id, action));
+            },
         };
 
         None
     }
+
+    fn backend_call(&self, cmd: Command) -> Event {
+        Event::BackendCall(self.term.id, cmd)
+    }
+
+    /// Inputs that the pressed key is looked up by, from the most specific
+    fn key_inputs(
+        key: &Key,
+        physical_key: Physical,
+        modifiers: Modifiers,
+    ) -> Vec<InputKind> {
+        let mut inputs = match key {
+            // Use the physical character key for bindings even when text is None (e.g., Ctrl/Cmd combos)
+            Key::Character(k) => vec![InputKind::Char(k.to_ascii_lowercase())],
+            Key::Named(code) => vec![InputKind::KeyCode(*code)],
+            Key::Unidentified => return Vec::new(),
+        };
+        if let Physical::Code(code) = physical_key {
+            inputs.push(InputKind::Physical(code));
+        }
+        // Shortcuts on non-latin layouts (e.g. Ctrl+C on
+        // a cyrillic one) fall back to the latin character
+        // of the same physical key
+        if let Key::Character(k) = key {
+            if modifiers
+                .intersects(Modifiers::CTRL | Modifiers::ALT | Modifiers::LOGO)
+            {
+                if let Some(latin) = key.to_latin(physical_key) {
+                    let latin = latin.to_ascii_lowercase().to_string();
+                    if latin != k.to_ascii_lowercase() {
+                        inputs.push(InputKind::Char(latin));
+                    }
+                }
+            }
+        }
+
+        inputs
+    }
+
+    fn is_modifier_key(key: &Key) -> bool {
+        matches!(
+            key,
+            Key::Named(
+                Named::Shift
+                    | Named::Control
+                    | Named::Alt
+                    | Named::AltGraph
+                    | Named::Super
+                    | Named::Meta
+                    | Named::Hyper
+                    | Named::Fn
+            )
+        )
+    }
+
+    /// Feeds the pressed key to the chord bindings. Returns `None`
+    /// if the key is not a part of any chord, otherwise the key is consumed
+    /// and the action of the completed chord (or `Ignore`) is returned.
+    fn process_chord(
+        bindings: &BindingsLayout,
+        timeout: Duration,
+        state: &mut TerminalViewState,
+        key: ChordKey,
+        terminal_mode: TermMode,
+        now: Instant,
+    ) -> Option<BindingAction> {
+        if state.chord_deadline.is_some_and(|deadline| deadline <= now) {
+            state.clear_chord();
+        }
+
+        match bindings.match_chord(&state.pending_chord, &key, terminal_mode) {
+            ChordMatch::Complete(action) => {
+                state.clear_chord();
+                Some(action)
+            },
+            ChordMatch::Prefix => {
+                state.pending_chord.push(key);
+                state.chord_deadline = Some(now + timeout);
+                Some(BindingAction::Ignore)
+            },
+            // The unknown key after a prefix cancels the chord
+            ChordMatch::Mismatch if !state.pending_chord.is_empty() => {
+                state.clear_chord();
+                Some(BindingAction::Ignore)
+            },
+            ChordMatch::Mismatch => None,
+        }
+    }
+
+    /// Drops the pending chord when its time is out
+    /// or the widget has lost the focus
+    fn handle_chord_timeout(
+        &self,
+        event: &iced_core::Event,
+        state: &mut TerminalViewState,
+        shell: &mut iced_graphics::core::Shell<'_, Event>,
+    ) {
+        let Some(deadline) = state.chord_deadline else {
+            return;
+        };
+
+        let is_expired = matches!(
+            event,
+            iced::Event::Window(window::Event::RedrawRequested(now))
+                if *now >= deadline
+        );
+        if is_expired || !state.is_focused() {
+            state.clear_chord();
+            shell.request_redraw();
+        }
+    }
+
+    fn is_builtin_glyph(&self, c: char) -> bool {
+        self.term.font.builtin_box_drawing && is_builtin_glyph(c)
+    }
+
+    fn cell_glyph(
+        &self,
+        content: String,
+        position: Point,
+        flags: cell::Flags,
+        color: Color,
+    ) -> Text {
+        let c = content.chars().next().unwrap_or_default();
+        Text {
+            font: self.term.font.glyph_font(c, flags),
+            content,
+            position,
+            size: iced_core::Pixels(self.term.font.size),
+            color,
+            align_x: Alignment::Center,
+            align_y: Vertical::Center,
+            shaping: Shaping::Advanced,
+            line_height: LineHeight::Relative(self.term.font.scale_factor),
+            ..Default::default()
+        }
+    }
+
+    /// The run is centered on its cells, so the drift of the glyphs
+    /// is spread to both ends of the run
+    fn run_glyph(&self, run: TextRun, cell_width: f32, center_y: f32) -> Text {
+        let mut content = run.content;
+        content.truncate(content.trim_end().len());
+        let center_x =
+            (run.start_column as f32 + content.len() as f32 * 0.5) * cell_width;
+
+        Text {
+            content,
+            position: Point::new(center_x, center_y),
+            font: run.font,
+            size: iced_core::Pixels(self.term.font.size),
+            color: run.color,
+            align_x: Alignment::Center,
+            align_y: Vertical::Center,
+            shaping: Shaping::Basic,
+            line_height: LineHeight::Relative(self.term.font.scale_factor),
+            ..Default::default()
+        }
+    }
+
+    /// Draws the image rows attached to the cells, the images with
+    /// the negative z-index are drawn below the text and the rest over it
+    #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+    fn draw_images(
+        &self,
+        renderer: &mut iced::Renderer,
+        layout: iced::advanced::Layout,
+    ) {
+        use iced::advanced::image::{Image, Renderer as _};
+        use iced::advanced::Renderer as _;
+
+        let content = self.term.backend.renderable_content();
+        if content.images.is_empty() {
+            return;
+        }
+
+        let store = self
+            .term
+            .backend
+            .graphics
+            .lock()
+            .expect("graphics store is poisoned");
+        let mut rows: Vec<_> = content
+            .images
+            .iter()
+            .filter_map(|row| {
+                let placement = store.placement(row.placement_id)?;
+                let image = store.image(placement.image_id)?;
+                Some((row, placement, image))
+            })
+            .collect();
+        rows.sort_by_key(|(_, placement, _)| placement.z_index);
+
+        let bounds = layout.bounds();
+        let cell = Size::new(
+            content.terminal_size.cell_width as f32,
+            content.terminal_size.cell_height as f32,
+        );
+        let draw_row = |renderer: &mut iced::Renderer,
+                        (row, placement, image)| {
+            let Some((image_bounds, clip_bounds)) =
+                Placement::row_bounds(placement, image, row, bounds, cell)
+            else {
+                return;
+            };
+            renderer.draw_image(
+                Image::new(image.handle.clone()),
+                image_bounds,
+                clip_bounds,
+            );
+        };
+
+        // The images are drawn over the cell backgrounds and below the text
+        // of their layer
+        let above =
+            rows.partition_point(|(_, placement, _)| placement.z_index < 0);
+        for row in &rows[..above] {
+            draw_row(renderer, *row);
+        }
+        renderer.with_layer(bounds, |renderer| {
+            for row in &rows[above..] {
+                draw_row(renderer, *row);
+            }
+        });
+    }
+
+    /// Draws the line of the viewport in the widget coordinates
+    fn draw_line(
+        &self,
+        frame: &mut Frame,
+        state: &TerminalViewState,
+        line: usize,
+    ) {
+        let content = self.term.backend.renderable_content();
+        let term_size = content.terminal_size;
+        let cell_width = term_size.cell_width as f32;
+        let cell_height = term_size.cell_height as f32;
+        let cell_size = Size::new(cell_width, cell_height);
+        let y = line as f32 * cell_height;
+        let cell_center_y = y + cell_height * 0.5;
+        // We use the background pallete color as a default
+        // because the widget global background color must be the same
+        let default_bg = self
+            .term
+            .theme
+            .get_color(ansi::Color::Named(NamedColor::Background));
+
+        let grid_line =
+            Line(line as i32 - content.grid.display_offset() as i32);
+        let mut bg_batch_rect =
+            BackgroundRect::default().with_cell_height(cell_height);
+        let mut decorations = Vec::new();
+        let mut glyphs = Vec::new();
+        let mut text_run: Option<TextRun> = None;
+        let max_run_columns =
+            max_run_columns(self.term.font.measure.width, cell_width);
+
+        for (column, cell) in content.grid[grid_line].iter().enumerate() {
+            let point = TerminalGridPoint::new(grid_line, Column(column));
+            let x = column as f32 * cell_width;
+
+            // Resolve colors for this cell
+            let mut fg = self.term.theme.get_foreground(cell.fg, cell.flags);
+            let mut bg = self.term.theme.get_color(cell.bg);
+
+            // Handle inverse and selected text
+            let inverse = cell.flags.contains(cell::Flags::INVERSE);
+            if inverse {
+                std::mem::swap(&mut fg, &mut bg);
+            }
+            // Without the selection colors, the selected text is inverted
+            // and the selected inverse text keeps its colors
+            if is_cell_selected(content.selectable_range, point, cell.flags) {
+                match self.term.theme.selection_colors(fg, bg) {
+                    Some(colors) => (fg, bg) = colors,
+                    None if !inverse => std::mem::swap(&mut fg, &mut bg),
+                    None => {},
+                }
+            }
+
+            // Batch draw backgrounds: skip default background (container already paints it)
+            if bg != default_bg {
+                if bg_batch_rect.can_extend(bg, x) {
+                    // Same color and contiguous: extend current run
+                    bg_batch_rect.extend(cell_width);
+                } else {
+                    // New colored run (or non-contiguous): flush previous run if any
+                    if bg_batch_rect.can_flush() {
+                        frame
+                            .fill(&bg_batch_rect.build(y), bg_batch_rect.color);
+                    }
+
+                    // Start a new run but do not draw yet; wait for potential extensions
+                    bg_batch_rect = BackgroundRect::default()
+                        .with_cell_height(cell_height)
+                        .activate()
+                        .with_color(bg)
+                        .with_start_x(x)
+                        .with_width(cell_width);
+                }
+            } else if bg_batch_rect.can_flush() {
+                // Background returns to default, flush current background rect and init the new one
+                frame.fill(&bg_batch_rect.build(y), bg_batch_rect.color);
+
+                bg_batch_rect =
+                    BackgroundRect::default().with_cell_height(cell_height);
+            }
+
+            // Decorations and builtin glyphs are drawn after the
+            // backgrounds, the batched background runs are flushed later
+            let is_hovered_hyperlink =
+                content.hovered_hyperlink.as_ref().is_some_and(|range| {
+                    range.contains(&point)
+                        && range.contains(&state.mouse_position_on_grid)
+                });
+            let underline = UnderlineStyle::from_flags(cell.flags)
+                .map(|style| {
+                    let color = cell
+                        .underline_color()
+                        .map_or(fg, |c| self.term.theme.get_color(c));
+                    (style, color)
+                })
+                .or(is_hovered_hyperlink
+                    .then_some((UnderlineStyle::Single, fg)));
+            let is_hidden = cell.flags.contains(cell::Flags::HIDDEN);
+            if let Some((style, color)) = underline.filter(|_| !is_hidden) {
+                decorations.push(Decoration {
+                    kind: DecorationKind::Underline(style),
+                    position: Point::new(x, y),
+                    cell_size,
+                    color,
+                });
+            }
+            if cell.flags.contains(cell::Flags::STRIKEOUT) && !is_hidden {
+                decorations.push(Decoration {
+                    kind: DecorationKind::Strikeout,
+                    position: Point::new(x, y),
+                    cell_size,
+                    color: fg,
+                });
+            }
+
+            // Draw text, the cursor is drawn over it without the cache.
+            // ASCII cells are batched into runs, other glyphs may not fit
+            // the monospace advance, so they are centered on their cells
+            match cell_text(cell) {
+                Some(_) if self.is_builtin_glyph(cell.c) => {
+                    glyphs.push(BuiltinGlyph {
+                        c: cell.c,
+                        position: Point::new(x, y),
+                        cell_size,
+                        color: fg,
+                    });
+                },
+                Some(text) if text.len() == 1 && cell.c.is_ascii() => {
+                    let font = self.term.font.face(cell.flags);
+                    match &mut text_run {
+                        Some(run)
+                            if run.can_extend(column, max_run_columns)
+                                && run.font == font
+                                && run.color == fg =>
+                        {
+                            run.content.push(cell.c);
+                        },
+                        _ => {
+                            let run = TextRun::new(column, cell.c, font, fg);
+                            if let Some(run) = text_run.replace(run) {
+                                frame.fill_text(self.run_glyph(
+                                    run,
+                                    cell_width,
+                                    cell_center_y,
+                                ));
+                            }
+                        },
+                    }
+                },
+                Some(text) => {
+                    // The wide char is centered between its two cells
+                    let center_x =
+                        x + cell_width * cell_columns(cell.flags) * 0.5;
+                    frame.fill_text(self.cell_glyph(
+                        text,
+                        Point::new(center_x, cell_center_y),
+                        cell.flags,
+                        fg,
+                    ));
+                },
+                // Spaces don't break the run, the trailing ones are trimmed
+                None if cell.c == ' ' => {
+                    if let Some(run) = text_run
+                        .as_mut()
+                        .filter(|run| run.can_extend(column, max_run_columns))
+                    {
+                        run.content.push(' ');
+                    }
+                },
+                None => {},
+            }
+        }
+
+        if let Some(run) = text_run {
+            frame.fill_text(self.run_glyph(run, cell_width, cell_center_y));
+        }
+
+        // Flush any remaining background run at the end
+        if bg_batch_rect.can_flush() {
+            frame.fill(&bg_batch_rect.build(y), bg_batch_rect.color);
+        }
+
+        for decoration in &decorations {
+            decoration.draw(frame);
+        }
+        for glyph in &glyphs {
+            glyph.draw(frame);
+        }
+    }
+
+    fn draw_cursor(
+        &self,
+        state: &TerminalViewState,
+        renderer: &iced::Renderer,
+        layout: iced::advanced::Layout,
+        viewport: &Rectangle,
+    ) -> Option<Frame> {
+        let content = self.term.backend.renderable_content();
+        if !content.terminal_mode.contains(TermMode::SHOW_CURSOR) {
+            return None;
+        }
+
+        let shape = cursor_shape(
+            content.cursor_style,
+            state.is_focused(),
+            self.term.is_cursor_visible(Instant::now()),
+        )?;
+
+        // The cursor scrolled out of the viewport is not drawn
+        let mut point = content.grid.cursor_point();
+        let line = point.line.0 + content.grid.display_offset() as i32;
+        if line < 0 || line >= content.grid.screen_lines() as i32 {
+            return None;
+        }
+
+        // The cursor over the wide char spacer covers the whole wide char
+        if point.column.0 > 0
+            && content.grid[point]
+                .flags
+                .contains(cell::Flags::WIDE_CHAR_SPACER)
+        {
+            point.column -= 1;
+        }
+
+        let cell_width = content.terminal_size.cell_width as f32;
+        let cell_height = content.terminal_size.cell_height as f32;
+        let position = Point::new(
+            layout.position().x + point.column.0 as f32 * cell_width,
+            layout.position().y + line as f32 * cell_height,
+        );
+        let cell = &content.grid[point];
+        let cursor_size =
+            Size::new(cell_width * cell_columns(cell.flags), cell_height);
+        let (color, text_color) = self.term.theme.cursor_colors(
+            self.term.theme.get_color(content.cursor.fg),
+            self.term.theme.get_color(cell.bg),
+        );
+
+        let mut frame = Frame::new(renderer, viewport.size());
+        match shape {
+            CursorShape::Block => {
+                frame.fill_rectangle(position, cursor_size, color);
+
+                // The glyph under the block cursor is drawn again
+                // in the color that contrasts with the cursor
+                if let Some(content) = cell_text(cell) {
+                    if self.is_builtin_glyph(cell.c) {
+                        BuiltinGlyph {
+                            c: cell.c,
+                            position,
+                            cell_size: Size::new(cell_width, cell_height),
+                            color: text_color,
+                        }
+                        .draw(&mut frame);
+                        return Some(frame);
+                    }
+
+                    frame.fill_text(self.cell_glyph(
+                        content,
+                        Point::new(
+                            position.x + cursor_size.width * 0.5,
+                            position.y + cell_height * 0.5,
+                        ),
+                        cell.flags,
+                        text_color,
+                    ));
+                }
+            },
+            CursorShape::Underline => {
+                let thickness = (cell_height * 0.1).max(1.0);
+                frame.fill_rectangle(
+                    Point::new(
+                        position.x,
+                        position.y + cell_height - thickness,
+                    ),
+                    Size::new(cursor_size.width, thickness),
+                    color,
+                );
+            },
+            CursorShape::Beam => {
+                let thickness = (cell_width * 0.15).max(1.0);
+                frame.fill_rectangle(
+                    position,
+                    Size::new(thickness, cell_height),
+                    color,
+                );
+            },
+            CursorShape::HollowBlock | CursorShape::Hidden => {
+                let thickness = 1.0;
+                let outline = Path::rectangle(
+                    Point::new(
+                        position.x + thickness * 0.5,
+                        position.y + thickness * 0.5,
+                    ),
+                    Size::new(
+                        cursor_size.width - thickness,
+                        cell_height - thickness,
+                    ),
+                );
+                frame.stroke(
+                    &outline,
+                    Stroke::default().with_width(thickness).with_color(color),
+                );
+            },
+        }
+
+        Some(frame)
+    }
+
+    fn draw_pending_chord(
+        &self,
+        state: &TerminalViewState,
+        renderer: &iced::Renderer,
+        layout: iced::advanced::Layout,
+        viewport: &Rectangle,
+    ) -> Option<Frame> {
+        if state.pending_chord.is_empty() {
+            return None;
+        }
+
+        let content = state
+            .pending_chord
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(" ");
+        let measure = self.term.font.measure;
+        let bounds = layout.bounds();
+        let size = Size::new(
+            (content.chars().count() + 2) as f32 * measure.width,
+            measure.height,
+        );
+        let position = Point::new(
+            bounds.x + bounds.width - size.width,
+            bounds.y + bounds.height - size.height,
+        );
+
+        let mut frame = Frame::new(renderer, viewport.size());
+        frame.fill_rectangle(
+            position,
+            size,
+            self.term
+                .theme
+                .get_color(ansi::Color::Named(NamedColor::Foreground)),
+        );
+        frame.fill_text(Text {
+            content,
+            position: Point::new(
+                position.x + size.width * 0.5,
+                position.y + size.height * 0.5,
+            ),
+            font: self.term.font.font_type,
+            size: iced_core::Pixels(self.term.font.size),
+            color: self
+                .term
+                .theme
+                .get_color(ansi::Color::Named(NamedColor::Background)),
+            align_x: Alignment::Center,
+            align_y: Vertical::Center,
+            shaping: Shaping::Advanced,
+            line_height: LineHeight::Relative(self.term.font.scale_factor),
+            ..Default::default()
+        });
+
+        Some(frame)
+    }
+
+    fn handle_alt_side(
+        state: &mut TerminalViewState,
+        event: &iced::keyboard::Event,
+    ) {
+        use iced::keyboard::Event::{
+            KeyPressed, KeyReleased, ModifiersChanged,
+        };
+
+        match event {
+            KeyPressed {
+                key: Key::Named(Named::Alt),
+                location,
+                ..
+            } => match location {
+                Location::Left => state.left_alt_pressed = true,
+                Location::Right => state.right_alt_pressed = true,
+                _ => {},
+            },
+            KeyReleased {
+                key: Key::Named(Named::Alt),
+                location,
+                ..
+            } => match location {
+                Location::Left => state.left_alt_pressed = false,
+                Location::Right => state.right_alt_pressed = false,
+                _ => {},
+            },
+            // Release events can be lost while the window is unfocused
+            ModifiersChanged(m) if !m.alt() => {
+                state.left_alt_pressed = false;
+                state.right_alt_pressed = false;
+            },
+            _ => {},
+        }
+    }
+
+    fn is_meta_pressed(
+        alt_as_meta: AltAsMeta,
+        state: &TerminalViewState,
+    ) -> bool {
+        if !state.keyboard_modifiers.alt() {
+            return false;
+        }
+
+        match alt_as_meta {
+            AltAsMeta::Disabled => false,
+            AltAsMeta::OnlyLeft => state.left_alt_pressed,
+            AltAsMeta::OnlyRight => state.right_alt_pressed,
+            AltAsMeta::Both => true,
+        }
+    }
+
+    /// Builds the `ESC` prefixed input for the key pressed with Alt as Meta.
+    /// The key is resolved without Alt, so Ctrl+Alt+C sends `ESC ^C`
+    /// and Alt+Shift+1 sends `ESC !`. The `modified` key is used
+    /// for Shift unless Option composed it on macOS.
+    fn meta_input(
+        bindings: &BindingsLayout,
+        inputs: &[InputKind],
+        key: &str,
+        modified: &str,
+        modifiers: Modifiers,
+        terminal_mode: TermMode,
+    ) -> Vec<u8> {
+        let mut input = vec![b'\x1b'];
+        match bindings.get_action_with_fallbacks(
+            inputs,
+            modifiers.difference(Modifiers::ALT),
+            terminal_mode,
+        ) {
+            BindingAction::Char(c) => {
+                let mut buf = [0, 0, 0, 0];
+                input.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            },
+            BindingAction::Esc(seq) => input.extend_from_slice(seq.as_bytes()),
+            _ if modifiers.shift() => {
+                input.extend_from_slice(Self::shifted(key, modified).as_bytes())
+            },
+            _ => input.extend_from_slice(key.as_bytes()),
+        }
+
+        input
+    }
+
+    /// Returns the key with Shift applied. The composed characters,
+    /// e.g. `ı` of Option+Shift+B, are replaced by the US layout symbols.
+    fn shifted(key: &str, modified: &str) -> String {
+        const KEYS: &str = "`1234567890-=[]\\;',./";
+        const SHIFTED: &str = "~!@#$%^&*()_+{}|:\"<>?";
+
+        if key.chars().all(char::is_alphabetic) {
+            return key.to_uppercase();
+        }
+        if modified.is_ascii() {
+            return modified.to_string();
+        }
+        key.chars()
+            .map(|c| match KEYS.find(c) {
+                Some(index) => SHIFTED.as_bytes()[index] as char,
+                None => c,
+            })
+            .collect()
+    }
 }
 
 impl Widget<Event, Theme, iced::Renderer> for TerminalView<'_> {
@@ -448,200 +1238,50 @@ impl Widget<Event, Theme, iced::Renderer> for TerminalView<'_> {
     ) {
         let state = tree.state.downcast_ref::<TerminalViewState>();
         let content = self.term.backend.renderable_content();
-        let term_size = content.terminal_size;
-        let cell_width = term_size.cell_width as f32;
-        let cell_height = term_size.cell_height as f32;
-        let font_size = self.term.font.size;
-        let font_scale_factor = self.term.font.scale_factor;
-        let layout_offset_x = layout.position().x;
-        let layout_offset_y = layout.position().y;
-
-        let geom = self.term.cache.draw(renderer, viewport.size(), |frame| {
-            // Precompute constants used in the inner loop
-            let display_offset = content.grid.display_offset() as f32;
-            let cell_size = Size::new(cell_width, cell_height);
-            let half_w = cell_width * 0.5;
-            let half_h = cell_height * 0.5;
-            // We use the background pallete color as a default
-            // because the widget global background color must be the same
-            let default_bg = self
-                .term
-                .theme
-                .get_color(ansi::Color::Named(NamedColor::Background));
-
-            let mut last_line: Option<i32> = None;
-            let mut bg_batch_rect = BackgroundRect::default();
-
-            for indexed in content.grid.display_iter() {
-                // Compute per-cell geometry cheaply
-                let line = indexed.point.line.0;
-                let col = indexed.point.column.0 as f32;
-
-                // Resolve position point for this cell
-                let x = layout_offset_x + (col * cell_width);
-                let y = layout_offset_y
-                    + (((line as f32) + display_offset) * cell_height);
-                let cell_center_y = y + half_h;
-                let cell_center_x = x + half_w;
-
-                // Resolve colors for this cell
-                let mut fg = self.term.theme.get_color(indexed.fg);
-                let mut bg = self.term.theme.get_color(indexed.bg);
-                // Pre-swap background: the block cursor is painted in the
-                // cell's (pre-swap) fg, so this is the contrasting color
-                // for the glyph under it regardless of INVERSE/selection.
-                let cell_bg = bg;
-
-                // If the new line was detected,
-                // need to flush pending background rect and init the new one
-                if last_line != Some(line) {
-                    if bg_batch_rect.can_flush() {
-                        let line = last_line.unwrap_or(line);
-                        frame.fill(
-                            &bg_batch_rect.build(line),
-                            bg_batch_rect.color,
-                        );
-                    }
-
-                    last_line = Some(line);
-                    bg_batch_rect = BackgroundRect::default()
-                        .with_cell_height(cell_height)
-                        .with_display_offset(display_offset)
-                        .with_layout_offset_y(layout_offset_y);
-                }
-
-                // Handle dim, inverse, and selected text
-                if indexed
-                    .cell
-                    .flags
-                    .intersects(cell::Flags::DIM | cell::Flags::DIM_BOLD)
-                {
-                    fg.a *= 0.7;
-                }
-                if indexed.cell.flags.contains(cell::Flags::INVERSE)
-                    || content
-                        .selectable_range
-                        .is_some_and(|r| r.contains(indexed.point))
-                {
-                    std::mem::swap(&mut fg, &mut bg);
-                }
+        let size = layout.bounds().size();
+
+        // Every line is cached on its own,
+        // only the damaged lines are drawn again
+        let geometries: Vec<_> = self
+            .term
+            .line_caches
+            .iter()
+            .take(content.grid.screen_lines())
+            .enumerate()
+            .map(|(line, cache)| {
+                cache.draw(renderer, size, |frame| {
+                    self.draw_line(frame, state, line)
+                })
+            })
+            .collect();
 
-                // Batch draw backgrounds: skip default background (container already paints it)
-                if bg != default_bg {
-                    if bg_batch_rect.can_extend(bg, x) {
-                        // Same color and contiguous: extend current run
-                        bg_batch_rect.extend(cell_width);
-                    } else {
-                        // New colored run (or non-contiguous): flush previous run if any
-                        if bg_batch_rect.can_flush() {
-                            frame.fill(
-                                &bg_batch_rect.build(line),
-                                bg_batch_rect.color,
-                            );
-                        }
-
-                        // Start a new run but do not draw yet; wait for potential extensions
-                        bg_batch_rect = BackgroundRect::default()
-                            .with_cell_height(cell_height)
-                            .with_display_offset(display_offset)
-                            .with_layout_offset_y(layout_offset_y)
-                            .activate()
-                            .with_color(bg)
-                            .with_start_x(x)
-                            .with_width(cell_width);
-                    }
-                } else if bg_batch_rect.can_flush() {
-                    // Background returns to default, flush current background rect and init the new one
-                    frame.fill(&bg_batch_rect.build(line), bg_batch_rect.color);
-
-                    bg_batch_rect = BackgroundRect::default()
-                        .with_cell_height(cell_height)
-                        .with_display_offset(display_offset)
-                        .with_layout_offset_y(layout_offset_y);
-                }
-
-                // Draw hovered hyperlink underline (rare; keep per-cell for correctness)
-                if content.hovered_hyperlink.as_ref().is_some_and(|range| {
-                    range.contains(&indexed.point)
-                        && range.contains(&state.mouse_position_on_grid)
-                }) || indexed.cell.flags.contains(cell::Flags::UNDERLINE)
-                {
-                    let underline_height = y + cell_size.height;
-                    let underline = Path::line(
-                        Point::new(x, underline_height),
-                        Point::new(x + cell_size.width, underline_height),
-                    );
-                    frame.stroke(
-                        &underline,
-                        Stroke::default()
-                            .with_width(font_size * 0.15)
-                            .with_color(fg),
-                    );
-                }
-
-                // Handle cursor rendering
-                if content.grid.cursor.point == indexed.point
-                    && content.terminal_mode.contains(TermMode::SHOW_CURSOR)
-                {
-                    let cursor_color =
-                        self.term.theme.get_color(content.cursor.fg);
-                    let cursor_rect =
-                        Path::rectangle(Point::new(x, y), cell_size);
-                    frame.fill(&cursor_rect, cursor_color);
+        use iced::advanced::graphics::geometry::Renderer as _;
+        use iced::advanced::Renderer as _;
+        renderer.with_translation(
+            Vector::new(layout.position().x, layout.position().y),
+            |renderer| {
+                for geometry in geometries {
+                    renderer.draw_geometry(geometry);
                 }
+            },
+        );
 
-                // Draw text
-                if indexed.c != ' ' && indexed.c != '\t' {
-                    // The glyph under the block cursor must contrast with
-                    // the cursor rect (painted above in the cell's pre-swap
-                    // fg). Using the post-swap bg — or gating this on
-                    // APP_CURSOR, a keypad mode unrelated to rendering —
-                    // made the glyph invisible whenever the cell was
-                    // INVERSE or inside a selection.
-                    if content.grid.cursor.point == indexed.point
-                        && content.terminal_mode.contains(TermMode::SHOW_CURSOR)
-                    {
-                        fg = cell_bg;
-                    }
-                    // Resolve font style (bold/italic) from cell flags
-                    let mut font = self.term.font.font_type;
-                    if indexed
-                        .cell
-                        .flags
-                        .intersects(cell::Flags::BOLD | cell::Flags::DIM_BOLD)
-                    {
-                        font.weight = FontWeight::Bold;
-                    }
-                    if indexed.cell.flags.contains(cell::Flags::ITALIC) {
-                        font.style = FontStyle::Italic;
-                    }
-                    let text = Text {
-                        content: indexed.cell.c.to_string(),
-                        position: Point::new(cell_center_x, cell_center_y),
-                        font,
-                        size: iced_core::Pixels(font_size),
-                        color: fg,
-                        align_x: Alignment::Center,
-                        align_y: Vertical::Center,
-                        shaping: Shaping::Advanced,
-                        line_height: LineHeight::Relative(font_scale_factor),
-                        ..Default::default()
-                    };
-                    frame.fill_text(text);
-                }
-            }
+        #[cfg(any(feature = "sixel", feature = "kitty-graphics"))]
+        self.draw_images(renderer, layout);
 
-            // Flush any remaining background run at the end
-            if bg_batch_rect.can_flush() {
-                frame.fill(
-                    &bg_batch_rect.build(last_line.unwrap_or(0)),
-                    bg_batch_rect.color,
-                );
-            }
-        });
+        // The cursor is not cached,
+        // it changes with the focus and blinking phase
+        if let Some(frame) = self.draw_cursor(state, renderer, layout, viewport)
+        {
+            renderer.draw_geometry(frame.into_geometry());
+        }
 
-        use iced::advanced::graphics::geometry::Renderer as _;
-        renderer.draw_geometry(geom);
+        // The chord indicator is not cached, it is shown only for a while
+        if let Some(frame) =
+            self.draw_pending_chord(state, renderer, layout, viewport)
+        {
+            renderer.draw_geometry(frame.into_geometry());
+        }
     }
 
     fn update(
@@ -659,12 +1299,20 @@ impl Widget<Event, Theme, iced::Renderer> for TerminalView<'_> {
         self.handle_resize(state, layout, shell);
 
         let is_cursor_in_layout = self.is_cursor_in_layout(cursor, layout);
+        let is_focused = state.is_focused();
         self.handle_focus(event, state, is_cursor_in_layout);
+        if state.is_focused() != is_focused {
+            // The cursor is hollow while the widget is unfocused
+            shell.request_redraw();
+        }
+        self.handle_chord_timeout(event, state, shell);
 
-        let commands = match event {
+        let pending_chord_len = state.pending_chord.len();
+        let events: Vec<Event> = match event {
             iced::Event::Mouse(mouse_event) if is_cursor_in_layout => self
                 .handle_mouse_event(
                     state,
+                    clipboard,
                     layout.position(),
                     cursor.position().unwrap(),
                     mouse_event,
@@ -681,12 +1329,21 @@ impl Widget<Event, Theme, iced::Renderer> for TerminalView<'_> {
             _ => Vec::new(),
         };
 
-        if !commands.is_empty() {
+        if !events.is_empty() || state.pending_chord.len() != pending_chord_len
+        {
             shell.capture_event();
         }
 
-        for cmd in commands {
-            shell.publish(Event::BackendCall(self.term.id, cmd));
+        if state.pending_chord.len() != pending_chord_len {
+            shell.request_redraw();
+        }
+
+        if let Some(deadline) = state.chord_deadline {
+            shell.request_redraw_at(deadline);
+        }
+
+        for event in events {
+            shell.publish(event);
         }
     }
 
@@ -729,8 +1386,12 @@ struct TerminalViewState {
     last_click: Option<mouse::Click>,
     scroll_pixels: f32,
     keyboard_modifiers: Modifiers,
+    left_alt_pressed: bool,
+    right_alt_pressed: bool,
     size: Size<f32>,
     mouse_position_on_grid: TerminalGridPoint,
+    pending_chord: Vec<ChordKey>,
+    chord_deadline: Option<Instant>,
     terminal_id: u64,
 }
 
@@ -742,11 +1403,20 @@ impl TerminalViewState {
             last_click: None,
             scroll_pixels: 0.0,
             keyboard_modifiers: Modifiers::empty(),
+            left_alt_pressed: false,
+            right_alt_pressed: false,
             size: Size::from([0.0, 0.0]),
             mouse_position_on_grid: TerminalGridPoint::default(),
+            pending_chord: Vec::new(),
+            chord_deadline: None,
             terminal_id,
         }
     }
+
+    fn clear_chord(&mut self) {
+        self.pending_chord.clear();
+        self.chord_deadline = None;
+    }
 }
 
 impl operation::Focusable for TerminalViewState {
@@ -763,11 +1433,96 @@ impl operation::Focusable for TerminalViewState {
     }
 }
 
+/// The char of the cell with its combining marks,
+/// `None` if there is nothing to draw
+fn cell_text(cell: &cell::Cell) -> Option<String> {
+    let skipped = cell::Flags::WIDE_CHAR_SPACER
+        | cell::Flags::LEADING_WIDE_CHAR_SPACER
+        | cell::Flags::HIDDEN;
+    if cell.flags.intersects(skipped) {
+        return None;
+    }
+
+    let zerowidth = cell.zerowidth().unwrap_or_default();
+    if (cell.c == ' ' || cell.c == '\t') && zerowidth.is_empty() {
+        return None;
+    }
+
+    let mut text = String::with_capacity(4 * (zerowidth.len() + 1));
+    text.push(cell.c);
+    text.extend(zerowidth);
+    Some(text)
+}
+
+/// How many columns the glyph of the cell takes
+fn cell_columns(flags: cell::Flags) -> f32 {
+    if flags.contains(cell::Flags::WIDE_CHAR) {
+        2.0
+    } else {
+        1.0
+    }
+}
+
+/// Both cells of the wide char are selected
+/// when any of them is inside the selection
+fn is_cell_selected(
+    range: Option<SelectionRange>,
+    point: TerminalGridPoint,
+    flags: cell::Flags,
+) -> bool {
+    let Some(range) = range else {
+        return false;
+    };
+
+    range.contains(point)
+        || (flags.contains(cell::Flags::WIDE_CHAR)
+            && range
+                .contains(TerminalGridPoint::new(point.line, point.column + 1)))
+        || (flags.contains(cell::Flags::WIDE_CHAR_SPACER)
+            && point.column.0 > 0
+            && range
+                .contains(TerminalGridPoint::new(point.line, point.column - 1)))
+}
+
+/// Contiguous ASCII cells of the line with the same font and color,
+/// they are drawn as one text instead of a text per cell
+struct TextRun {
+    content: String,
+    start_column: usize,
+    font: Font,
+    color: Color,
+}
+
+impl TextRun {
+    fn new(start_column: usize, c: char, font: Font, color: Color) -> Self {
+        Self {
+            content: String::from(c),
+            start_column,
+            font,
+            color,
+        }
+    }
+
+    fn can_extend(&self, column: usize, max_columns: usize) -> bool {
+        self.start_column + self.content.len() == column
+            && self.content.len() < max_columns
+    }
+}
+
+/// How many cells the text run can take before its glyphs, placed with
+/// the font advance, drift from their cells by more than half a pixel
+fn max_run_columns(advance: f32, cell_width: f32) -> usize {
+    let drift = (advance - cell_width).abs();
+    if drift < f32::EPSILON {
+        usize::MAX
+    } else {
+        ((1.0 / drift) as usize).max(1)
+    }
+}
+
 #[derive(Default)]
 struct BackgroundRect {
-    display_offset: f32,
     cell_height: f32,
-    layout_offset_y: f32,
     is_active: bool,
     color: Color,
     start_x: f32,
@@ -775,21 +1530,11 @@ struct BackgroundRect {
 }
 
 impl BackgroundRect {
-    fn with_display_offset(mut self, value: f32) -> Self {
-        self.display_offset = value;
-        self
-    }
-
     fn with_cell_height(mut self, value: f32) -> Self {
         self.cell_height = value;
         self
     }
 
-    fn with_layout_offset_y(mut self, value: f32) -> Self {
-        self.layout_offset_y = value;
-        self
-    }
-
     fn with_width(mut self, value: f32) -> Self {
         self.width = value;
         self
@@ -810,11 +1555,9 @@ impl BackgroundRect {
         self
     }
 
-    fn build(&self, line: i32) -> Path {
-        let flush_y = self.layout_offset_y
-            + ((line as f32 + self.display_offset) * self.cell_height);
+    fn build(&self, y: f32) -> Path {
         Path::rectangle(
-            Point::new(self.start_x, flush_y),
+            Point::new(self.start_x, y),
             Size::new(self.width, self.cell_height),
         )
     }
@@ -1004,8 +1747,10 @@ mod tests {
         fn generates_drag_update_command_when_dragged_in_mouse_motion_mode() {
             let mut state = TerminalViewState::new(0);
             state.is_dragged = true; // Simulate an ongoing drag operation
-            let mut terminal_content = RenderableContent::default();
-            terminal_content.terminal_mode = TermMode::MOUSE_MOTION;
+            let terminal_content = RenderableContent {
+                terminal_mode: TermMode::MOUSE_MOTION,
+                ..Default::default()
+            };
             let layout_position = Point { x: 5.0, y: 5.0 };
             let cursor_position = Point { x: 100.0, y: 150.0 };
             let mut commands = Vec::new();
@@ -1041,8 +1786,10 @@ mod tests {
             // widget's own selection.
             let mut state = TerminalViewState::new(0);
             state.is_dragged = true;
-            let mut terminal_content = RenderableContent::default();
-            terminal_content.terminal_mode = TermMode::MOUSE_DRAG;
+            let terminal_content = RenderableContent {
+                terminal_mode: TermMode::MOUSE_DRAG,
+                ..Default::default()
+            };
             let layout_position = Point { x: 5.0, y: 5.0 };
             let cursor_position = Point { x: 100.0, y: 150.0 };
             let mut commands = Vec::new();
@@ -1077,8 +1824,10 @@ mod tests {
             let mut state = TerminalViewState::new(0);
             state.keyboard_modifiers = Modifiers::SHIFT;
             state.is_dragged = true; // Simulate an ongoing drag operation
-            let mut terminal_content = RenderableContent::default();
-            terminal_content.terminal_mode = TermMode::SGR_MOUSE;
+            let terminal_content = RenderableContent {
+                terminal_mode: TermMode::SGR_MOUSE,
+                ..Default::default()
+            };
             let layout_position = Point { x: 5.0, y: 5.0 };
             let cursor_position = Point { x: 100.0, y: 150.0 };
             let mut commands = Vec::new();
@@ -1103,8 +1852,10 @@ mod tests {
             let mut state = TerminalViewState::new(0);
             state.keyboard_modifiers = Modifiers::COMMAND;
             state.is_dragged = true; // Simulate an ongoing drag operation
-            let mut terminal_content = RenderableContent::default();
-            terminal_content.terminal_mode = TermMode::SGR_MOUSE;
+            let terminal_content = RenderableContent {
+                terminal_mode: TermMode::SGR_MOUSE,
+                ..Default::default()
+            };
             let layout_position = Point { x: 5.0, y: 5.0 };
             let cursor_position = Point { x: 100.0, y: 150.0 };
             let mut commands = Vec::new();
@@ -1137,6 +1888,7 @@ mod tests {
 
     mod handle_button_released_tests {
         use super::*;
+        use crate::bindings::Binding;
         use alacritty_terminal::index::{Column, Line};
 
         #[test]
@@ -1241,6 +1993,33 @@ mod tests {
                 ),
             ));
         }
+
+        #[test]
+        fn custom_action_on_button_release() {
+            let mut state = TerminalViewState::new(0);
+            state.keyboard_modifiers = Modifiers::CTRL;
+            let mut bindings = BindingsLayout::new();
+            bindings.add_bindings(vec![(
+                Binding {
+                    target: InputKind::Mouse(iced_core::mouse::Button::Left),
+                    modifiers: Modifiers::CTRL,
+                    terminal_mode_include: TermMode::empty(),
+                    terminal_mode_exclude: TermMode::empty(),
+                },
+                BindingAction::Custom("open".into()),
+            )]);
+            let mut commands = Vec::new();
+
+            let action = TerminalView::handle_button_released(
+                &mut state,
+                &TermMode::empty(),
+                &bindings,
+                &mut commands,
+            );
+
+            assert!(commands.is_empty());
+            assert_eq!(action, BindingAction::Custom("open".into()));
+        }
     }
 
     mod handle_wheel_scrolled_tests {
@@ -1318,4 +2097,300 @@ mod tests {
             assert_eq!(state.scroll_pixels, -5.4000034);
         }
     }
+
+    mod chord_tests {
+        use super::*;
+        use crate::bindings::{KeyboardBinding, KeyboardChord};
+
+        fn chord_key(key: &str) -> ChordKey {
+            let binding: KeyboardBinding = key.parse().unwrap();
+            ChordKey {
+                inputs: vec![binding.target],
+                modifiers: binding.modifiers,
+            }
+        }
+
+        fn bindings() -> BindingsLayout {
+            let chord: KeyboardChord =
+                vec!["ctrl+a".parse().unwrap(), "c".parse().unwrap()];
+            let mut bindings = BindingsLayout::new();
+            bindings.add_chord_bindings(vec![(
+                chord,
+                BindingAction::Custom("new".into()),
+            )]);
+            bindings
+        }
+
+        #[test]
+        fn completes_chord_before_timeout() {
+            let bindings = bindings();
+            let mut state = TerminalViewState::new(0);
+            let timeout = Duration::from_secs(1);
+            let now = Instant::now();
+
+            let action = TerminalView::process_chord(
+                &bindings,
+                timeout,
+                &mut state,
+                chord_key("ctrl+a"),
+                TermMode::empty(),
+                now,
+            );
+            assert_eq!(action, Some(BindingAction::Ignore));
+            assert_eq!(state.pending_chord, vec![chord_key("ctrl+a")]);
+            assert_eq!(state.chord_deadline, Some(now + timeout));
+
+            let action = TerminalView::process_chord(
+                &bindings,
+                timeout,
+                &mut state,
+                chord_key("c"),
+                TermMode::empty(),
+                now + Duration::from_millis(500),
+            );
+            assert_eq!(action, Some(BindingAction::Custom("new".into())));
+            assert!(state.pending_chord.is_empty());
+            assert_eq!(state.chord_deadline, None);
+        }
+
+        #[test]
+        fn chord_expires_after_timeout() {
+            let bindings = bindings();
+            let mut state = TerminalViewState::new(0);
+            let timeout = Duration::from_secs(1);
+            let now = Instant::now();
+
+            TerminalView::process_chord(
+                &bindings,
+                timeout,
+                &mut state,
+                chord_key("ctrl+a"),
+                TermMode::empty(),
+                now,
+            );
+            let action = TerminalView::process_chord(
+                &bindings,
+                timeout,
+                &mut state,
+                chord_key("c"),
+                TermMode::empty(),
+                now + timeout,
+            );
+            assert_eq!(action, None);
+            assert!(state.pending_chord.is_empty());
+        }
+
+        #[test]
+        fn unknown_key_cancels_chord() {
+            let bindings = bindings();
+            let mut state = TerminalViewState::new(0);
+            let timeout = Duration::from_secs(1);
+            let now = Instant::now();
+
+            assert_eq!(
+                TerminalView::process_chord(
+                    &bindings,
+                    timeout,
+                    &mut state,
+                    chord_key("x"),
+                    TermMode::empty(),
+                    now,
+                ),
+                None
+            );
+
+            TerminalView::process_chord(
+                &bindings,
+                timeout,
+                &mut state,
+                chord_key("ctrl+a"),
+                TermMode::empty(),
+                now,
+            );
+            let action = TerminalView::process_chord(
+                &bindings,
+                timeout,
+                &mut state,
+                chord_key("x"),
+                TermMode::empty(),
+                now,
+            );
+            assert_eq!(action, Some(BindingAction::Ignore));
+            assert!(state.pending_chord.is_empty());
+            assert_eq!(state.chord_deadline, None);
+        }
+    }
+
+    mod alt_as_meta_tests {
+        use super::*;
+
+        #[test]
+        fn meta_pressed_on_configured_side() {
+            let cases = vec![
+                (AltAsMeta::Disabled, true, true, false),
+                (AltAsMeta::OnlyLeft, true, false, true),
+                (AltAsMeta::OnlyLeft, false, true, false),
+                (AltAsMeta::OnlyRight, true, false, false),
+                (AltAsMeta::OnlyRight, false, true, true),
+                (AltAsMeta::Both, false, false, true),
+            ];
+
+            for (alt_as_meta, left, right, expected) in cases {
+                let mut state = TerminalViewState::new(0);
+                state.keyboard_modifiers = Modifiers::ALT;
+                state.left_alt_pressed = left;
+                state.right_alt_pressed = right;
+                assert_eq!(
+                    TerminalView::is_meta_pressed(alt_as_meta, &state),
+                    expected
+                );
+            }
+        }
+
+        #[test]
+        fn alt_side_released_with_modifiers() {
+            let mut state = TerminalViewState::new(0);
+            state.left_alt_pressed = true;
+            state.right_alt_pressed = true;
+
+            TerminalView::handle_alt_side(
+                &mut state,
+                &iced::keyboard::Event::ModifiersChanged(Modifiers::SHIFT),
+            );
+
+            assert!(!state.left_alt_pressed);
+            assert!(!state.right_alt_pressed);
+        }
+
+        #[test]
+        fn meta_input_prefixes_escape() {
+            let bindings = BindingsLayout::new();
+            let cases = vec![
+                ("b", "b", Modifiers::ALT, vec![0x1b, b'b']),
+                (
+                    "b",
+                    "B",
+                    Modifiers::ALT | Modifiers::SHIFT,
+                    vec![0x1b, b'B'],
+                ),
+                (
+                    "1",
+                    "!",
+                    Modifiers::ALT | Modifiers::SHIFT,
+                    vec![0x1b, b'!'],
+                ),
+                // Option composes the modified keys on macOS
+                (
+                    "b",
+                    "ı",
+                    Modifiers::ALT | Modifiers::SHIFT,
+                    vec![0x1b, b'B'],
+                ),
+                (
+                    "1",
+                    "⁄",
+                    Modifiers::ALT | Modifiers::SHIFT,
+                    vec![0x1b, b'!'],
+                ),
+                (
+                    "/",
+                    "¿",
+                    Modifiers::ALT | Modifiers::SHIFT,
+                    vec![0x1b, b'?'],
+                ),
+                ("c", "c", Modifiers::ALT | Modifiers::CTRL, vec![0x1b, 0x03]),
+            ];
+
+            for (key, modified, modifiers, expected) in cases {
+                let input = TerminalView::meta_input(
+                    &bindings,
+                    &[InputKind::Char(key.to_string())],
+                    key,
+                    modified,
+                    modifiers,
+                    TermMode::empty(),
+                );
+                assert_eq!(input, expected);
+            }
+        }
+    }
+
+    mod cell_tests {
+        use super::*;
+        use alacritty_terminal::index::{Column, Line};
+
+        #[test]
+        fn compose_cell_text() {
+            let mut cell = cell::Cell {
+                c: 'e',
+                ..Default::default()
+            };
+            assert_eq!(cell_text(&cell), Some(String::from("e")));
+            cell.push_zerowidth('\u{301}');
+            assert_eq!(cell_text(&cell), Some(String::from("e\u{301}")));
+
+            // The emoji ZWJ sequence is kept in the first cell
+            let mut cell = cell::Cell {
+                c: '👩',
+                flags: cell::Flags::WIDE_CHAR,
+                ..Default::default()
+            };
+            cell.push_zerowidth('\u{200d}');
+            cell.push_zerowidth('💻');
+            assert_eq!(cell_text(&cell), Some(String::from("👩\u{200d}💻")));
+            assert_eq!(cell_columns(cell.flags), 2.0);
+
+            let blank = cell::Cell::default();
+            assert_eq!(cell_text(&blank), None);
+            let spacer = cell::Cell {
+                c: ' ',
+                flags: cell::Flags::WIDE_CHAR_SPACER,
+                ..Default::default()
+            };
+            assert_eq!(cell_text(&spacer), None);
+            let hidden = cell::Cell {
+                c: 'a',
+                flags: cell::Flags::HIDDEN,
+                ..Default::default()
+            };
+            assert_eq!(cell_text(&hidden), None);
+            assert_eq!(cell_columns(spacer.flags), 1.0);
+        }
+
+        #[test]
+        fn limit_text_run_by_drift() {
+            assert_eq!(max_run_columns(8.0, 8.0), usize::MAX);
+            assert_eq!(max_run_columns(8.25, 8.0), 4);
+            assert_eq!(max_run_columns(8.9, 8.0), 1);
+
+            let mut run = TextRun::new(2, 'a', Font::MONOSPACE, Color::WHITE);
+            assert!(run.can_extend(3, 2));
+            assert!(!run.can_extend(4, 2));
+            run.content.push('b');
+            assert!(!run.can_extend(4, 2));
+            assert!(run.can_extend(4, usize::MAX));
+        }
+
+        #[test]
+        fn select_both_cells_of_wide_char() {
+            let point =
+                |column| TerminalGridPoint::new(Line(0), Column(column));
+            let wide = cell::Flags::WIDE_CHAR;
+            let spacer = cell::Flags::WIDE_CHAR_SPACER;
+
+            // The selection ends on the wide char
+            let range = Some(SelectionRange::new(point(0), point(2), false));
+            assert!(is_cell_selected(range, point(2), wide));
+            assert!(is_cell_selected(range, point(3), spacer));
+            assert!(!is_cell_selected(range, point(4), wide));
+
+            // The selection starts on the spacer
+            let range = Some(SelectionRange::new(point(3), point(5), false));
+            assert!(is_cell_selected(range, point(2), wide));
+            assert!(is_cell_selected(range, point(3), spacer));
+            assert!(!is_cell_selected(range, point(1), cell::Flags::empty()));
+
+            assert!(!is_cell_selected(None, point(0), wide));
+        }
+    }
 }